@@ -0,0 +1,58 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use raytracer_challenge::camera::Camera;
+use raytracer_challenge::matrix::{Matrix, Matrix4};
+use raytracer_challenge::transformations::view_transform;
+use raytracer_challenge::tuple::{Point3, Vec3};
+use raytracer_challenge::world::{World, MAX_REFLECTIONS};
+use std::f64::consts::PI;
+
+const ELEMENTS: [f64; 16] = [
+    -5.0, 2.0, 6.0, -8.0, 1.0, -5.0, 1.0, 8.0, 7.0, 7.0, -6.0, -7.0, 1.0, -3.0, 7.0, 4.0,
+];
+
+// The heap matrix with the cofactor inverse against the stack matrix
+fn inverse(c: &mut Criterion) {
+    let m = Matrix::from_vector(4, &ELEMENTS);
+    c.bench_function("Matrix inverse", |b| b.iter(|| black_box(&m).try_inverse()));
+    let m4 = Matrix4::from_vector(&ELEMENTS);
+    c.bench_function("Matrix4 inverse", |b| {
+        b.iter(|| black_box(&m4).try_inverse())
+    });
+}
+
+fn multiply(c: &mut Criterion) {
+    let m = Matrix::from_vector(4, &ELEMENTS);
+    c.bench_function("Matrix multiply", |b| {
+        b.iter(|| black_box(m.clone()) * black_box(m.clone()))
+    });
+    let m4 = Matrix4::from_vector(&ELEMENTS);
+    c.bench_function("Matrix4 multiply", |b| {
+        b.iter(|| black_box(&m4) * black_box(&m4))
+    });
+    let p = Point3::new(1.0, 2.0, 3.0);
+    c.bench_function("Matrix4 multiply tuple", |b| {
+        b.iter(|| black_box(&m4) * black_box(p))
+    });
+}
+
+// The cost of one pixel of the default world, dominated by the transforms
+fn pixel(c: &mut Criterion) {
+    let world = World::default();
+    let mut camera = Camera::new(100, 50, PI / 3.0);
+    camera
+        .set_transform(view_transform(
+            Point3::new(0.0, 1.5, -5.0),
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+        ))
+        .unwrap();
+    c.bench_function("render pixel", |b| {
+        b.iter(|| {
+            let ray = camera.ray_for_pixel(black_box(50), black_box(25));
+            world.color_at(ray, MAX_REFLECTIONS)
+        })
+    });
+}
+
+criterion_group!(benches, inverse, multiply, pixel);
+criterion_main!(benches);
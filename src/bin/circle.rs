@@ -5,14 +5,15 @@ use raytracer_challenge::canvas::Canvas;
 use raytracer_challenge::file::*;
 use raytracer_challenge::intersections::hit;
 use raytracer_challenge::rays::Ray;
+use raytracer_challenge::shapes::Shape;
 use raytracer_challenge::spheres::Sphere;
 use raytracer_challenge::transformations::*;
-use raytracer_challenge::tuple::Tuple;
+use raytracer_challenge::tuple::{Color, Point3};
 
 fn main() {
   let canvas_width = 100;
   let canvas_height = 100;
-  let ray_origin = Tuple::point(0.0, 0.0, -5.0);
+  let ray_origin = Point3::new(0.0, 0.0, -5.0);
   let wall_z = 10.0;
   let wall_size = 7.0;
   let pixel_size = wall_size / canvas_width as f64;
@@ -21,13 +22,13 @@ fn main() {
   // shape.set_transform(scaling(0.5, 1.0, 1.0));
   shape.set_transform(shearing(1.0, 0.0, 0.0, 0.0, 0.0, 0.0) * scaling(0.5, 1.0, 1.0));
   let mut canvas = Canvas::new(canvas_width, canvas_height);
-  let color = Tuple::color(1.0, 0.0, 0.0);
+  let color = Color::new(1.0, 0.0, 0.0);
   println!("Starting circle...");
   for y in 0..canvas_height {
     let world_y = half - pixel_size * y as f64;
     for x in 0..canvas_width {
       let world_x = half - pixel_size * x as f64;
-      let position = Tuple::point(world_x, world_y, wall_z);
+      let position = Point3::new(world_x, world_y, wall_z);
       let r = Ray::new(ray_origin, (position - ray_origin).normalize());
       let xs = shape.intersect(r);
 
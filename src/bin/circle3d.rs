@@ -3,50 +3,56 @@ extern crate raytracer_challenge;
 
 use raytracer_challenge::canvas::Canvas;
 use raytracer_challenge::file::*;
-use raytracer_challenge::intersections::hit;
+use raytracer_challenge::intersections::{hit, prepare_computations};
 use raytracer_challenge::lights::PointLight;
 use raytracer_challenge::materials::Material;
 use raytracer_challenge::rays::Ray;
+use raytracer_challenge::shapes::Shape;
 use raytracer_challenge::spheres::Sphere;
-use raytracer_challenge::tuple::Tuple;
+use raytracer_challenge::tuple::{Color, Point3};
 use std::sync::Arc;
 
 fn main() {
   let canvas_width = 100;
   let canvas_height = 100;
-  let ray_origin = Tuple::point(0.0, 0.0, -5.0);
+  let ray_origin = Point3::new(0.0, 0.0, -5.0);
   let wall_z = 10.0;
   let wall_size = 7.0;
   let pixel_size = wall_size / canvas_width as f64;
   let half = wall_size / 2.0;
   let mut shape = Sphere::default();
   let mut mat = Material::default();
-  mat.color = Tuple::color(1.0, 0.2, 1.0);
+  mat.color = Color::new(1.0, 0.2, 1.0);
   shape.set_material(mat);
-  let light_position = Tuple::point(-10.0, 10.0, -10.0);
-  let light_color = Tuple::color(1.0, 1.0, 1.0);
+  let light_position = Point3::new(-10.0, 10.0, -10.0);
+  let light_color = Color::new(1.0, 1.0, 1.0);
   let light = PointLight::new(light_position, light_color);
 
   // shape.set_transform(scaling(0.5, 1.0, 1.0));
   // shape.set_transform(shearing(1.0, 0.0, 0.0, 0.0, 0.0, 0.0) * scaling(0.5, 1.0, 1.0));
   let mut canvas = Canvas::new(canvas_width, canvas_height);
-  let mut color: Tuple;
+  let mut color: Color;
   println!("Starting circle...");
   for y in 0..canvas_height {
     let world_y = half - pixel_size * y as f64;
     println!("Processing line...{} of {}", y, canvas_height);
     for x in 0..canvas_width {
       let world_x = -half + pixel_size * x as f64;
-      let position = Tuple::point(world_x, world_y, wall_z);
+      let position = Point3::new(world_x, world_y, wall_z);
       let r = Ray::new(ray_origin, (position - ray_origin).normalize());
       let xs = shape.intersect(r.clone());
 
-      if let Some(hit) = hit(xs) {
-        let point = r.clone().position(hit.t);
-        let normal = hit.object.normal_at(point);
-        let eye = -r.direction;
+      if let Some(hit) = hit(xs.clone()) {
+        let comps = prepare_computations(&hit, &r, &xs);
         let alight = Arc::new(light.clone());
-        color = hit.object.material.lighting(&alight, point, eye, normal);
+        color = comps.object.material().lighting(
+          comps.object,
+          alight.as_ref(),
+          comps.over_point,
+          comps.eyev,
+          comps.normalv,
+          1.0,
+        );
         canvas.write_pixel(x, y, color);
       }
     }
@@ -4,7 +4,7 @@ extern crate raytracer_challenge;
 use raytracer_challenge::canvas::Canvas;
 use raytracer_challenge::file::write_ppm_to_file;
 use raytracer_challenge::transformations;
-use raytracer_challenge::tuple::Tuple;
+use raytracer_challenge::tuple::{Color, Point3};
 use std::f64::consts::PI;
 
 fn main() {
@@ -12,13 +12,13 @@ fn main() {
   let canvas_height = 550;
   let radius = 3.0 / 8.0 * (canvas_width as f64);
   let mut canvas = Canvas::new(canvas_width, canvas_height);
-  let color = Tuple::color(1.0, 0.0, 0.0);
-  let twelve = Tuple::point(0.0, 0.0, 1.0);
+  let color = Color::new(1.0, 0.0, 0.0);
+  let twelve = Point3::new(0.0, 0.0, 1.0);
 
   println!("Starting clock...");
   for time in 0..12 {
     let r = transformations::rotation_y(f64::from(time) * PI / 6.0);
-    let Tuple(timepos_x, _, timepos_y, _) = r * twelve;
+    let Point3(timepos_x, _, timepos_y) = r * twelve;
 
     println!("Rotating clock...");
     let px = (timepos_x as f64 * radius + canvas_width as f64 / 2.0).round() as usize;
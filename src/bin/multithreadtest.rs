@@ -6,12 +6,13 @@ extern crate image as im;
 extern crate piston_window;
 use piston_window::*;
 use raytracer_challenge::file::*;
-use raytracer_challenge::intersections::hit;
+use raytracer_challenge::intersections::{hit, prepare_computations};
 use raytracer_challenge::lights::PointLight;
 use raytracer_challenge::materials::Material;
 use raytracer_challenge::rays::Ray;
+use raytracer_challenge::shapes::Shape;
 use raytracer_challenge::spheres::Sphere;
-use raytracer_challenge::tuple::Tuple;
+use raytracer_challenge::tuple::{Color, Point3};
 use std::sync::mpsc;
 use std::sync::Arc;
 use std::thread;
@@ -21,7 +22,7 @@ use threadpool::ThreadPool;
 pub struct Pixel {
     pub x: u32,
     pub y: u32,
-    pub c: Tuple,
+    pub c: Color,
 }
 
 fn main() {
@@ -99,18 +100,18 @@ fn perform_render(
     let n_workers = num_physical_cpus;
     let pool = ThreadPool::new(n_workers);
 
-    let ray_origin = Tuple::point(0.0, 0.0, -5.0);
+    let ray_origin = Point3::new(0.0, 0.0, -5.0);
     let wall_z = 10.0;
     let wall_size = 7.0;
     let pixel_size = wall_size / f64::from(canvas_width);
     let half = wall_size / 2.0;
     let mut shape = Sphere::default();
     let mut mat = Material::default();
-    mat.color = Tuple::color(1.0, 1.0, 0.2);
+    mat.color = Color::new(1.0, 1.0, 0.2);
     shape.set_material(mat);
     let ashape = Arc::new(shape);
-    let light_position = Tuple::point(-10.0, 10.0, -10.0);
-    let light_color = Tuple::color(1.0, 1.0, 1.0);
+    let light_position = Point3::new(-10.0, 10.0, -10.0);
+    let light_color = Color::new(1.0, 1.0, 1.0);
     let light = Arc::new(PointLight::new(light_position, light_color));
 
     // shape.set_transform(scaling(0.5, 1.0, 1.0));
@@ -127,18 +128,20 @@ fn perform_render(
             let mut pixels: Vec<Pixel> = Vec::new();
             for x in 0..canvas_width {
                 let world_x = -half + pixel_size * f64::from(x);
-                let position = Tuple::point(world_x, world_y, wall_z);
+                let position = Point3::new(world_x, world_y, wall_z);
                 let r = Ray::new(ray_origin, (position - ray_origin).normalize());
                 let xs = shape_clone.intersect(r.clone());
 
-                if let Some(hit) = hit(xs) {
-                    let point = r.clone().position(hit.t);
-                    let normal = hit.object.normal_at(point);
-                    let eye = -r.direction;
-                    let color = hit
-                        .object
-                        .material
-                        .lighting(&light_clone, point, eye, normal);
+                if let Some(hit) = hit(xs.clone()) {
+                    let comps = prepare_computations(&hit, &r, &xs);
+                    let color = comps.object.material().lighting(
+                        comps.object,
+                        light_clone.as_ref(),
+                        comps.over_point,
+                        comps.eyev,
+                        comps.normalv,
+                        1.0,
+                    );
                     pixels.push(Pixel { x, y, c: color });
                 }
             }
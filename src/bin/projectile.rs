@@ -3,27 +3,27 @@ extern crate raytracer_challenge;
 
 use raytracer_challenge::canvas::Canvas;
 use raytracer_challenge::file::write_ppm_to_file;
-use raytracer_challenge::tuple::Tuple;
+use raytracer_challenge::tuple::{Color, Point3, Vec3};
 
 fn main() {
   let mut iterations: u64 = 0;
   let mut p = Projectile {
-    position: Tuple::point(0.0, 1.0, 0.0),
-    velocity: Tuple::vector(1.0, 1.8, 0.0).normalize() * 11.25,
+    position: Point3::new(0.0, 1.0, 0.0),
+    velocity: Vec3::new(1.0, 1.8, 0.0).normalize() * 11.25,
   };
   let e = Environment {
-    gravity: Tuple::vector(0.0, -0.1, 0.0),
-    wind: Tuple::vector(-0.01, 0.0, 0.0),
+    gravity: Vec3::new(0.0, -0.1, 0.0),
+    wind: Vec3::new(-0.01, 0.0, 0.0),
   };
   let mut canvas = Canvas::new(900, 550);
   let mut mag;
   let mut color;
-  //  = Tuple::color(1.0, 0.0, 1.0 - mag);
+  //  = Color::new(1.0, 0.0, 1.0 - mag);
   println!("Starting projectile...");
   while p.position.1 > 0.0 && iterations < 1000 {
     iterations += 1;
     mag = 1.0 / p.velocity.magnitude() * 4.0;
-    color = Tuple::color(1.0, 0.0 + mag, 1.0 - mag);
+    color = Color::new(1.0, 0.0 + mag, 1.0 - mag);
     println!(
       "Iterating...tick {}, x:{:.2}, y: {:.2}",
       iterations, p.position.0, p.position.1
@@ -49,11 +49,11 @@ fn tick(env: &Environment, proj: &Projectile) -> Projectile {
 
 #[derive(Debug)]
 struct Projectile {
-  position: Tuple,
-  velocity: Tuple,
+  position: Point3,
+  velocity: Vec3,
 }
 
 struct Environment {
-  gravity: Tuple,
-  wind: Tuple,
+  gravity: Vec3,
+  wind: Vec3,
 }
@@ -0,0 +1,90 @@
+extern crate chrono;
+extern crate raytracer_challenge;
+
+use raytracer_challenge::camera::Camera;
+use raytracer_challenge::file::*;
+use raytracer_challenge::lights::PointLight;
+use raytracer_challenge::materials::Material;
+use raytracer_challenge::planes::Plane;
+use raytracer_challenge::shapes::Shape;
+use raytracer_challenge::spheres::Sphere;
+use raytracer_challenge::transformations::*;
+use raytracer_challenge::tuple::{Color, Point3, Vec3};
+use raytracer_challenge::world::World;
+use std::f64::consts::PI;
+
+fn main() {
+  let mut floor = Plane::default();
+  let mut floor_material = Material::default();
+  floor_material.color = Color::new(1.0, 0.9, 0.9);
+  floor_material.specular = 0.0;
+  floor.set_material(floor_material.clone());
+
+  let mut left_wall = Plane::default();
+  left_wall
+    .set_transform(translation(0.0, 0.0, 5.0) * rotation_y(-PI / 4.0) * rotation_x(PI / 2.0));
+  left_wall.set_material(floor_material.clone());
+
+  let mut right_wall = Plane::default();
+  right_wall
+    .set_transform(translation(0.0, 0.0, 5.0) * rotation_y(PI / 4.0) * rotation_x(PI / 2.0));
+  right_wall.set_material(floor_material);
+
+  let mut middle = Sphere::default();
+  middle.set_transform(translation(-0.5, 1.0, 0.5));
+  let mut middle_material = Material::default();
+  middle_material.color = Color::new(0.1, 1.0, 0.5);
+  middle_material.diffuse = 0.7;
+  middle_material.specular = 0.3;
+  middle.set_material(middle_material);
+
+  let mut right = Sphere::default();
+  right.set_transform(translation(1.5, 0.5, -0.5) * scaling(0.5, 0.5, 0.5));
+  let mut right_material = Material::default();
+  right_material.color = Color::new(0.5, 1.0, 0.1);
+  right_material.diffuse = 0.7;
+  right_material.specular = 0.3;
+  right.set_material(right_material);
+
+  let mut left = Sphere::default();
+  left.set_transform(translation(-1.5, 0.33, -0.75) * scaling(0.33, 0.33, 0.33));
+  let mut left_material = Material::default();
+  left_material.color = Color::new(1.0, 0.8, 0.1);
+  left_material.diffuse = 0.7;
+  left_material.specular = 0.3;
+  left.set_material(left_material);
+
+  let mut world = World::new();
+  world.objects = vec![
+    Box::new(floor),
+    Box::new(left_wall),
+    Box::new(right_wall),
+    Box::new(middle),
+    Box::new(right),
+    Box::new(left),
+  ];
+  world.lights.push(Box::new(PointLight::new(
+    Point3::new(-10.0, 10.0, -10.0),
+    Color::new(1.0, 1.0, 1.0),
+  )));
+
+  let mut camera = Camera::new(200, 100, PI / 3.0);
+  camera
+    .set_transform(view_transform(
+      Point3::new(0.0, 1.5, -5.0),
+      Point3::new(0.0, 1.0, 0.0),
+      Vec3::new(0.0, 1.0, 0.0),
+    ))
+    .expect("The camera transform is singular");
+
+  if let Err(e) = world.check_transforms() {
+    println!("Warning, the object will not be visible. {}", e);
+  }
+  println!("Rendering scene...");
+  let canvas = camera.render(&world);
+  println!("Writing canvas to ppm.");
+  let ppm = canvas.canvas_to_ppm();
+  println!("Writing ppm to file.");
+  write_ppm_to_file(&ppm, "Scene_test");
+  println!("Finished.");
+}
@@ -0,0 +1,175 @@
+use crate::canvas::Canvas;
+use crate::matrix::{Matrix4, SingularMatrix};
+use crate::rays::Ray;
+use crate::transformations::Transform;
+use crate::tuple::Point3;
+use crate::world::{World, MAX_REFLECTIONS};
+
+#[derive(Debug, Clone)]
+pub struct Camera {
+    hsize: usize,
+    vsize: usize,
+    field_of_view: f64,
+    transform: Transform,
+    pixel_size: f64,
+    half_width: f64,
+    half_height: f64,
+}
+
+impl Camera {
+    pub fn new(hsize: usize, vsize: usize, field_of_view: f64) -> Camera {
+        // The canvas is one unit in front of the camera, so half the width
+        // of the view is the tangent of half the field of view
+        let half_view = (field_of_view / 2.0).tan();
+        let aspect = hsize as f64 / vsize as f64;
+        let (half_width, half_height) = if aspect >= 1.0 {
+            (half_view, half_view / aspect)
+        } else {
+            (half_view * aspect, half_view)
+        };
+        Camera {
+            hsize,
+            vsize,
+            field_of_view,
+            transform: Transform::identity(),
+            pixel_size: (half_width * 2.0) / hsize as f64,
+            half_width,
+            half_height,
+        }
+    }
+
+    // The size and field of view are fixed at construction, since the pixel
+    // size and the half extents of the canvas are derived from them
+    pub fn hsize(&self) -> usize {
+        self.hsize
+    }
+
+    pub fn vsize(&self) -> usize {
+        self.vsize
+    }
+
+    pub fn field_of_view(&self) -> f64 {
+        self.field_of_view
+    }
+
+    pub fn pixel_size(&self) -> f64 {
+        self.pixel_size
+    }
+
+    pub fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    // A camera needs the inverse for every ray, so a singular transform is
+    // refused and the previous one kept
+    pub fn set_transform(&mut self, t: Matrix4) -> Result<(), SingularMatrix> {
+        let transform = Transform::new(t);
+        transform.try_inverse()?;
+        self.transform = transform;
+        Ok(())
+    }
+
+    pub fn ray_for_pixel(&self, px: usize, py: usize) -> Ray {
+        // The offset from the edge of the canvas to the pixel's center
+        let xoffset = (px as f64 + 0.5) * self.pixel_size;
+        let yoffset = (py as f64 + 0.5) * self.pixel_size;
+
+        // The untransformed coordinates of the pixel in world space.
+        // The camera looks toward -z, so +x is to the left.
+        let world_x = self.half_width - xoffset;
+        let world_y = self.half_height - yoffset;
+
+        // Transform the canvas point and the origin, then compute the ray's direction.
+        // The canvas is at z = -1
+        let inverse = self.transform.inverse();
+        let pixel = inverse * Point3::new(world_x, world_y, -1.0);
+        let origin = inverse * Point3::new(0.0, 0.0, 0.0);
+        let direction = (pixel - origin).normalize();
+        Ray::new(origin, direction)
+    }
+
+    pub fn render(&self, world: &World) -> Canvas {
+        let mut image = Canvas::new(self.hsize, self.vsize);
+        for y in 0..self.vsize {
+            for x in 0..self.hsize {
+                let ray = self.ray_for_pixel(x, y);
+                let color = world.color_at(ray, MAX_REFLECTIONS);
+                image.write_pixel(x, y, color);
+            }
+        }
+        image
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+
+mod tests {
+    use super::*;
+    use crate::transformations::{rotation_y, scaling, translation, view_transform};
+    use crate::tuple::{Color, Vec3};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn constructing_a_camera() {
+        let c = Camera::new(160, 120, PI / 2.0);
+        assert_eq!(160, c.hsize());
+        assert_eq!(120, c.vsize());
+        assert_eq!(PI / 2.0, c.field_of_view());
+        assert_eq!(&Matrix4::identity(), c.transform());
+    }
+    #[test]
+    fn the_pixel_size_for_a_horizontal_canvas() {
+        let c = Camera::new(200, 125, PI / 2.0);
+        assert!((c.pixel_size() - 0.01).abs() < 1e-5);
+    }
+    #[test]
+    fn the_pixel_size_for_a_vertical_canvas() {
+        let c = Camera::new(125, 200, PI / 2.0);
+        assert!((c.pixel_size() - 0.01).abs() < 1e-5);
+    }
+    #[test]
+    fn constructing_a_ray_through_the_center_of_the_canvas() {
+        let c = Camera::new(201, 101, PI / 2.0);
+        let r = c.ray_for_pixel(100, 50);
+        assert_eq!(Point3::new(0.0, 0.0, 0.0), r.origin);
+        assert_eq!(Vec3::new(0.0, 0.0, -1.0), r.direction);
+    }
+    #[test]
+    fn constructing_a_ray_through_a_corner_of_the_canvas() {
+        let c = Camera::new(201, 101, PI / 2.0);
+        let r = c.ray_for_pixel(0, 0);
+        assert_eq!(Point3::new(0.0, 0.0, 0.0), r.origin);
+        assert_eq!(Vec3::new(0.66519, 0.33259, -0.66851), r.direction);
+    }
+    #[test]
+    fn constructing_a_ray_when_the_camera_is_transformed() {
+        let mut c = Camera::new(201, 101, PI / 2.0);
+        c.set_transform(rotation_y(PI / 4.0) * translation(0.0, -2.0, 5.0))
+            .unwrap();
+        let r = c.ray_for_pixel(100, 50);
+        assert_eq!(Point3::new(0.0, 2.0, -5.0), r.origin);
+        assert_eq!(
+            Vec3::new(2_f64.sqrt() / 2.0, 0.0, -(2_f64.sqrt()) / 2.0),
+            r.direction
+        );
+    }
+    #[test]
+    fn a_camera_refuses_a_singular_transform() {
+        let mut c = Camera::new(201, 101, PI / 2.0);
+        c.set_transform(translation(0.0, -2.0, 5.0)).unwrap();
+        assert_eq!(Err(SingularMatrix), c.set_transform(scaling(1.0, 0.0, 1.0)));
+        assert_eq!(&translation(0.0, -2.0, 5.0), c.transform());
+    }
+    #[test]
+    fn rendering_a_world_with_a_camera() {
+        let w = World::default();
+        let mut c = Camera::new(11, 11, PI / 2.0);
+        let from = Point3::new(0.0, 0.0, -5.0);
+        let to = Point3::new(0.0, 0.0, 0.0);
+        let up = Vec3::new(0.0, 1.0, 0.0);
+        c.set_transform(view_transform(from, to, up)).unwrap();
+        let image = c.render(&w);
+        assert_eq!(Color::new(0.38066, 0.47583, 0.2855), image.pixel_at(5, 5));
+    }
+}
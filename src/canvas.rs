@@ -1,28 +1,29 @@
-use crate::tuple::Tuple;
+use crate::tuple::Color;
 const COLORSCALE: i32 = 255;
 
+#[derive(Debug)]
 pub struct Canvas {
     pub width: usize,
     pub height: usize,
-    pub canvas: Vec<Tuple>,
+    pub canvas: Vec<Color>,
 }
 
 impl Canvas {
     pub fn new(w: usize, h: usize) -> Canvas {
-        let c = vec![Tuple::color(0.0, 0.0, 0.0); w * h];
+        let c = vec![Color::new(0.0, 0.0, 0.0); w * h];
         Canvas {
             width: w,
             height: h,
             canvas: c,
         }
     }
-    pub fn write_pixel(&mut self, x: usize, y: usize, color: Tuple) {
+    pub fn write_pixel(&mut self, x: usize, y: usize, color: Color) {
         if x <= self.width && y <= self.height {
             let pos = self.width * y + x;
             self.canvas[pos] = color;
         }
     }
-    pub fn pixel_at(&self, x: usize, y: usize) -> Tuple {
+    pub fn pixel_at(&self, x: usize, y: usize) -> Color {
         let pos = self.width * y + x;
         self.canvas[pos]
     }
@@ -65,7 +66,7 @@ impl Canvas {
         for y in 0..self.height {
             let mut linecolors: Vec<i32> = Vec::new();
             for x in 0..self.width {
-                let Tuple(r, g, b, _) = self.pixel_at(x, y);
+                let Color(r, g, b) = self.pixel_at(x, y);
                 linecolors.push(convert_and_clamp_color(r));
                 linecolors.push(convert_and_clamp_color(g));
                 linecolors.push(convert_and_clamp_color(b));
@@ -86,7 +87,7 @@ mod tests {
     #[test]
     fn creating_a_canvas() {
         let c = Canvas::new(10, 20);
-        let black = Tuple::color(0.0, 0.0, 0.0);
+        let black = Color::new(0.0, 0.0, 0.0);
         assert_eq!(10, c.width);
         assert_eq!(20, c.height);
         for pixel in c.canvas {
@@ -96,7 +97,7 @@ mod tests {
     #[test]
     fn writing_pixel_to_canvas() {
         let mut c = Canvas::new(10, 20);
-        let red = Tuple::color(1.0, 0.0, 0.0);
+        let red = Color::new(1.0, 0.0, 0.0);
 
         c.write_pixel(2, 3, red);
         let actual = c.pixel_at(2, 3);
@@ -117,9 +118,9 @@ mod tests {
     #[test]
     fn constructing_the_ppm_pixel_data() {
         let mut c = Canvas::new(5, 3);
-        let c1 = Tuple::color(1.5, 0.0, 0.0);
-        let c2 = Tuple::color(0.0, 0.5, 0.0);
-        let c3 = Tuple::color(-0.5, 0.0, 1.0);
+        let c1 = Color::new(1.5, 0.0, 0.0);
+        let c2 = Color::new(0.0, 0.5, 0.0);
+        let c3 = Color::new(-0.5, 0.0, 1.0);
         c.write_pixel(0, 0, c1);
         c.write_pixel(2, 1, c2);
         c.write_pixel(4, 2, c3);
@@ -136,7 +137,7 @@ mod tests {
     #[test]
     fn splitting_long_lines_in_ppm_files() {
         let mut c = Canvas::new(10, 2);
-        let color = Tuple::color(1.0, 0.8, 0.6);
+        let color = Color::new(1.0, 0.8, 0.6);
         for i in 0..10 {
             for j in 0..2 {
                 c.write_pixel(i, j, color);
@@ -158,5 +159,4 @@ mod tests {
         let mut ppm = c.canvas_to_ppm();
         assert_eq!(Some('\n'), ppm.pop());
     }
-
 }
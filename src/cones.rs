@@ -0,0 +1,249 @@
+use crate::intersections::{Intersection, EPSILON};
+use crate::materials::Material;
+use crate::matrix::Matrix4;
+use crate::rays::Ray;
+use crate::shapes::Shape;
+use crate::transformations::Transform;
+use crate::tuple::{Point3, Vec3};
+
+// A double napped cone around the y axis with its tip at the origin,
+// the radius at any y equals the absolute value of y
+#[derive(Debug, PartialEq, Clone)]
+pub struct DoubleCone {
+    pub transform: Transform,
+    pub material: Material,
+    parent_transform: Transform,
+    pub minimum: f64,
+    pub maximum: f64,
+    pub closed: bool,
+}
+
+impl Default for DoubleCone {
+    fn default() -> DoubleCone {
+        DoubleCone {
+            transform: Transform::identity(),
+            material: Material::default(),
+            parent_transform: Transform::identity(),
+            minimum: f64::NEG_INFINITY,
+            maximum: f64::INFINITY,
+            closed: false,
+        }
+    }
+}
+
+impl DoubleCone {
+    fn intersect_caps<'a>(&'a self, ray: &Ray, xs: &mut Vec<Intersection<'a>>) {
+        if !self.closed || ray.direction.1.abs() < EPSILON {
+            return;
+        }
+        for y in [self.minimum, self.maximum].iter() {
+            let t = (y - ray.origin.1) / ray.direction.1;
+            if check_cap(ray, t, y.abs()) {
+                xs.push(Intersection::new(t, self));
+            }
+        }
+    }
+}
+
+// Is the intersection at t within the radius of the cap
+fn check_cap(ray: &Ray, t: f64, radius: f64) -> bool {
+    let x = ray.origin.0 + t * ray.direction.0;
+    let z = ray.origin.2 + t * ray.direction.2;
+    (x * x + z * z) <= radius * radius
+}
+
+impl Shape for DoubleCone {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn set_transform(&mut self, t: Matrix4) {
+        self.transform = Transform::new(t);
+    }
+
+    fn parent_transform(&self) -> &Transform {
+        &self.parent_transform
+    }
+
+    fn set_parent_transform(&mut self, t: Matrix4) {
+        self.parent_transform = Transform::new(t);
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_material(&mut self, m: Material) {
+        self.material = m;
+    }
+
+    fn local_intersect(&self, ray: Ray) -> Vec<Intersection<'_>> {
+        let mut xs = Vec::new();
+        let Point3(ox, oy, oz) = ray.origin;
+        let Vec3(dx, dy, dz) = ray.direction;
+        let a = dx * dx - dy * dy + dz * dz;
+        let b = 2.0 * ox * dx - 2.0 * oy * dy + 2.0 * oz * dz;
+        let c = ox * ox - oy * oy + oz * oz;
+
+        let mut ts = Vec::new();
+        if a.abs() < EPSILON {
+            // The ray is parallel to one of the halves and hits the other one once
+            if b.abs() >= EPSILON {
+                ts.push(-c / (2.0 * b));
+            }
+        } else {
+            let disc = b * b - 4.0 * a * c;
+            // Rays grazing the surface may give a tiny negative discriminant
+            if disc < -EPSILON {
+                return xs;
+            }
+            let disc = disc.max(0.0);
+            let t0 = (-b - disc.sqrt()) / (2.0 * a);
+            let t1 = (-b + disc.sqrt()) / (2.0 * a);
+            ts.push(t0.min(t1));
+            ts.push(t0.max(t1));
+        }
+        for t in ts {
+            let y = oy + t * dy;
+            if self.minimum < y && y < self.maximum {
+                xs.push(Intersection::new(t, self));
+            }
+        }
+        self.intersect_caps(&ray, &mut xs);
+        xs
+    }
+
+    fn local_normal_at(&self, object_point: Point3) -> Vec3 {
+        let Point3(x, y, z) = object_point;
+        let dist = x * x + z * z;
+        if dist < y * y && y >= self.maximum - EPSILON {
+            Vec3::new(0.0, 1.0, 0.0)
+        } else if dist < y * y && y <= self.minimum + EPSILON {
+            Vec3::new(0.0, -1.0, 0.0)
+        } else if dist < EPSILON && y.abs() < EPSILON {
+            // The sides meet at the apex, where the slope normal would
+            // vanish, so the axis stands in for it
+            Vec3::new(0.0, y.signum(), 0.0)
+        } else {
+            let mut ny = dist.sqrt();
+            if y > 0.0 {
+                ny = -ny;
+            }
+            Vec3::new(x, ny, z)
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intersecting_a_cone_with_a_ray() {
+        let cases = vec![
+            (
+                Point3::new(0.0, 0.0, -5.0),
+                Vec3::new(0.0, 0.0, 1.0),
+                5.0,
+                5.0,
+            ),
+            (
+                Point3::new(0.0, 0.0, -5.0),
+                Vec3::new(1.0, 1.0, 1.0),
+                8.66025,
+                8.66025,
+            ),
+            (
+                Point3::new(1.0, 1.0, -5.0),
+                Vec3::new(-0.5, -1.0, 1.0),
+                4.55006,
+                49.44994,
+            ),
+        ];
+        let shape = DoubleCone::default();
+        for (origin, direction, t0, t1) in cases {
+            let r = Ray::new(origin, direction.normalize());
+            let xs = shape.local_intersect(r);
+            assert_eq!(2, xs.len());
+            assert!((t0 - xs[0].t).abs() < 1e-4);
+            assert!((t1 - xs[1].t).abs() < 1e-4);
+        }
+    }
+    #[test]
+    fn intersecting_a_cone_with_a_ray_parallel_to_one_of_its_halves() {
+        let shape = DoubleCone::default();
+        let direction = Vec3::new(0.0, 1.0, 1.0).normalize();
+        let r = Ray::new(Point3::new(0.0, 0.0, -1.0), direction);
+        let xs = shape.local_intersect(r);
+        assert_eq!(1, xs.len());
+        assert!((0.35355 - xs[0].t).abs() < EPSILON);
+    }
+    #[test]
+    fn intersecting_a_cones_end_caps() {
+        let cases = vec![
+            (Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 1.0, 0.0), 0),
+            (Point3::new(0.0, 0.0, -0.25), Vec3::new(0.0, 1.0, 1.0), 2),
+            (Point3::new(0.0, 0.0, -0.25), Vec3::new(0.0, 1.0, 0.0), 4),
+        ];
+        let shape = DoubleCone {
+            minimum: -0.5,
+            maximum: 0.5,
+            closed: true,
+            ..DoubleCone::default()
+        };
+        for (origin, direction, count) in cases {
+            let r = Ray::new(origin, direction.normalize());
+            assert_eq!(count, shape.local_intersect(r).len());
+        }
+    }
+    #[test]
+    fn computing_the_normal_vector_on_a_cone() {
+        let cases = vec![
+            (Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0)),
+            (
+                Point3::new(1.0, 1.0, 1.0),
+                Vec3::new(1.0, -(2_f64.sqrt()), 1.0),
+            ),
+            (Point3::new(-1.0, -1.0, 0.0), Vec3::new(-1.0, 1.0, 0.0)),
+        ];
+        let shape = DoubleCone::default();
+        for (point, normal) in cases {
+            assert_eq!(normal, shape.local_normal_at(point));
+        }
+    }
+    #[test]
+    fn the_normal_vector_at_the_apex_of_a_cone_is_the_axis() {
+        let shape = DoubleCone::default();
+        assert_eq!(
+            Vec3::new(0.0, 1.0, 0.0),
+            shape.local_normal_at(Point3::new(0.0, 0.0, 0.0))
+        );
+        assert_eq!(
+            Vec3::new(0.0, -1.0, 0.0),
+            shape.local_normal_at(Point3::new(0.0, -EPSILON / 2.0, 0.0))
+        );
+        assert_eq!(
+            Vec3::new(0.0, 1.0, 0.0),
+            shape.normal_at(Point3::new(0.0, 0.0, 0.0))
+        );
+    }
+    #[test]
+    fn the_normal_vector_on_a_cones_end_caps() {
+        let shape = DoubleCone {
+            minimum: -1.0,
+            maximum: 2.0,
+            closed: true,
+            ..DoubleCone::default()
+        };
+        assert_eq!(
+            Vec3::new(0.0, 1.0, 0.0),
+            shape.local_normal_at(Point3::new(0.5, 2.0, 0.5))
+        );
+        assert_eq!(
+            Vec3::new(0.0, -1.0, 0.0),
+            shape.local_normal_at(Point3::new(0.0, -1.0, 0.5))
+        );
+    }
+}
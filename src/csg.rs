@@ -0,0 +1,295 @@
+use crate::intersections::{intersections, Intersection};
+use crate::materials::Material;
+use crate::matrix::Matrix4;
+use crate::rays::Ray;
+use crate::shapes::{same_shape, Shape, SingularTransform};
+use crate::transformations::Transform;
+use crate::tuple::{Point3, Vec3};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CsgOperation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+// Constructive solid geometry - two shapes combined by a set operation.
+// Like a group, the transform of the csg is passed on to both children.
+#[derive(Debug)]
+pub struct Csg {
+    pub operation: CsgOperation,
+    transform: Transform,
+    parent_transform: Transform,
+    pub material: Material,
+    left: Box<dyn Shape>,
+    right: Box<dyn Shape>,
+}
+
+impl Csg {
+    pub fn new(
+        operation: CsgOperation,
+        mut left: Box<dyn Shape>,
+        mut right: Box<dyn Shape>,
+    ) -> Csg {
+        left.set_parent_transform(Matrix4::identity());
+        right.set_parent_transform(Matrix4::identity());
+        Csg {
+            operation,
+            transform: Transform::identity(),
+            parent_transform: Transform::identity(),
+            material: Material::default(),
+            left,
+            right,
+        }
+    }
+
+    pub fn left(&self) -> &dyn Shape {
+        self.left.as_ref()
+    }
+
+    pub fn right(&self) -> &dyn Shape {
+        self.right.as_ref()
+    }
+
+    // Keeps the intersections that are on the surface of the combined shape.
+    // The list must be sorted, the walk tracks whether we are inside each child.
+    pub fn filter_intersections<'a>(&self, xs: Vec<Intersection<'a>>) -> Vec<Intersection<'a>> {
+        let mut inl = false;
+        let mut inr = false;
+        let mut result = Vec::new();
+        for i in xs {
+            let lhit = self.left.includes(i.object);
+            if intersection_allowed(self.operation, lhit, inl, inr) {
+                result.push(i.clone());
+            }
+            if lhit {
+                inl = !inl;
+            } else {
+                inr = !inr;
+            }
+        }
+        result
+    }
+
+    fn update_children(&mut self) {
+        let t = self.parent_transform.matrix() * self.transform.matrix();
+        self.left.set_parent_transform(t);
+        self.right.set_parent_transform(t);
+    }
+}
+
+// lhit: the hit is on the left shape, inl/inr: the hit is inside the left/right shape
+pub fn intersection_allowed(op: CsgOperation, lhit: bool, inl: bool, inr: bool) -> bool {
+    match op {
+        CsgOperation::Union => (lhit && !inr) || (!lhit && !inl),
+        CsgOperation::Intersection => (lhit && inr) || (!lhit && inl),
+        CsgOperation::Difference => (lhit && !inr) || (!lhit && inl),
+    }
+}
+
+impl Shape for Csg {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn set_transform(&mut self, t: Matrix4) {
+        self.transform = Transform::new(t);
+        self.update_children();
+    }
+
+    fn parent_transform(&self) -> &Transform {
+        &self.parent_transform
+    }
+
+    fn set_parent_transform(&mut self, t: Matrix4) {
+        self.parent_transform = Transform::new(t);
+        self.update_children();
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    // The operands keep their own materials, a red cube minus a blue sphere
+    // stays red and blue. Hits are always on an operand, so this material is
+    // never used for shading.
+    fn set_material(&mut self, m: Material) {
+        self.material = m;
+    }
+
+    fn local_intersect(&self, ray: Ray) -> Vec<Intersection<'_>> {
+        let mut xs = self.left.intersect(ray.clone());
+        xs.extend(self.right.intersect(ray));
+        self.filter_intersections(intersections(xs))
+    }
+
+    fn local_normal_at(&self, _object_point: Point3) -> Vec3 {
+        unreachable!("A csg has no surface, normals are computed on its children")
+    }
+
+    fn check_transform(&self, path: &str) -> Result<(), SingularTransform> {
+        self.own_transform_check(path)?;
+        self.left.check_transform(&format!("{}.left", path))?;
+        self.right.check_transform(&format!("{}.right", path))
+    }
+
+    fn includes(&self, other: &dyn Shape) -> bool {
+        same_shape(self, other) || self.left.includes(other) || self.right.includes(other)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+
+mod tests {
+    use super::*;
+    use crate::cubes::Cube;
+    use crate::spheres::Sphere;
+    use crate::transformations::{scaling, translation};
+
+    #[test]
+    fn csg_is_created_with_an_operation_and_two_shapes() {
+        let c = Csg::new(
+            CsgOperation::Union,
+            Box::new(Sphere::default()),
+            Box::new(Cube::default()),
+        );
+        assert_eq!(CsgOperation::Union, c.operation);
+        assert!(c.includes(c.left()));
+        assert!(c.includes(c.right()));
+        assert_eq!(&Matrix4::identity(), c.left().parent_transform());
+    }
+    #[test]
+    fn evaluating_the_rule_for_a_csg_operation() {
+        use CsgOperation::*;
+        // operation, lhit, inl, inr, result
+        let rules = [
+            (Union, true, true, true, false),
+            (Union, true, true, false, true),
+            (Union, true, false, true, false),
+            (Union, true, false, false, true),
+            (Union, false, true, true, false),
+            (Union, false, true, false, false),
+            (Union, false, false, true, true),
+            (Union, false, false, false, true),
+            (Intersection, true, true, true, true),
+            (Intersection, true, true, false, false),
+            (Intersection, true, false, true, true),
+            (Intersection, true, false, false, false),
+            (Intersection, false, true, true, true),
+            (Intersection, false, true, false, true),
+            (Intersection, false, false, true, false),
+            (Intersection, false, false, false, false),
+            (Difference, true, true, true, false),
+            (Difference, true, true, false, true),
+            (Difference, true, false, true, false),
+            (Difference, true, false, false, true),
+            (Difference, false, true, true, true),
+            (Difference, false, true, false, true),
+            (Difference, false, false, true, false),
+            (Difference, false, false, false, false),
+        ];
+        for &(op, lhit, inl, inr, result) in rules.iter() {
+            assert_eq!(result, intersection_allowed(op, lhit, inl, inr));
+        }
+    }
+    #[test]
+    fn filtering_a_list_of_intersections() {
+        // operation, indices of the kept intersections
+        let cases = [
+            (CsgOperation::Union, [0, 3]),
+            (CsgOperation::Intersection, [1, 2]),
+            (CsgOperation::Difference, [0, 1]),
+        ];
+        for &(op, [x0, x1]) in cases.iter() {
+            let c = Csg::new(op, Box::new(Sphere::default()), Box::new(Cube::default()));
+            let xs = intersections(vec![
+                Intersection::new(1.0, c.left()),
+                Intersection::new(2.0, c.right()),
+                Intersection::new(3.0, c.left()),
+                Intersection::new(4.0, c.right()),
+            ]);
+            let result = c.filter_intersections(xs.clone());
+            assert_eq!(2, result.len());
+            assert_eq!(xs[x0], result[0]);
+            assert_eq!(xs[x1], result[1]);
+        }
+    }
+    #[test]
+    fn a_ray_misses_a_csg_object() {
+        let c = Csg::new(
+            CsgOperation::Union,
+            Box::new(Sphere::default()),
+            Box::new(Cube::default()),
+        );
+        let r = Ray::new(Point3::new(0.0, 2.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        assert!(c.local_intersect(r).is_empty());
+    }
+    #[test]
+    fn a_ray_hits_a_csg_object() {
+        let s1 = Sphere::default();
+        let mut s2 = Sphere::default();
+        s2.set_transform(translation(0.0, 0.0, 0.5));
+        let c = Csg::new(CsgOperation::Union, Box::new(s1), Box::new(s2));
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let xs = c.local_intersect(r);
+        assert_eq!(2, xs.len());
+        assert_eq!(4.0, xs[0].t);
+        assert!(same_shape(c.left(), xs[0].object));
+        assert_eq!(6.5, xs[1].t);
+        assert!(same_shape(c.right(), xs[1].object));
+    }
+    #[test]
+    fn a_csg_difference_cuts_a_hole() {
+        // A square hole drilled through the middle of a cube along z
+        let mut hole = Cube::default();
+        hole.set_transform(scaling(0.5, 0.5, 2.0));
+        let c = Csg::new(
+            CsgOperation::Difference,
+            Box::new(Cube::default()),
+            Box::new(hole),
+        );
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        assert!(c.local_intersect(r).is_empty());
+        let r = Ray::new(Point3::new(0.75, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        assert_eq!(2, c.local_intersect(r).len());
+    }
+    #[test]
+    fn transforming_a_csg_transforms_its_children() {
+        let mut c = Csg::new(
+            CsgOperation::Union,
+            Box::new(Sphere::default()),
+            Box::new(Cube::default()),
+        );
+        c.set_transform(translation(0.0, 0.0, 5.0));
+        assert_eq!(&translation(0.0, 0.0, 5.0), c.left().parent_transform());
+        assert_eq!(&translation(0.0, 0.0, 5.0), c.right().parent_transform());
+    }
+    #[test]
+    fn a_csg_reports_a_singular_transform_of_a_child() {
+        let mut flat = Cube::default();
+        flat.set_transform(scaling(0.0, 1.0, 1.0));
+        let c = Csg::new(
+            CsgOperation::Union,
+            Box::new(Sphere::default()),
+            Box::new(flat),
+        );
+        let error = c.check_transform("objects[3]").unwrap_err();
+        assert_eq!("Cube", error.shape);
+        assert_eq!("objects[3].right", error.path);
+    }
+    #[test]
+    fn setting_the_material_of_a_csg_leaves_its_operands_alone() {
+        let mut c = Csg::new(
+            CsgOperation::Difference,
+            Box::new(Cube::default()),
+            Box::new(Sphere::default()),
+        );
+        let mut m = Material::default();
+        m.ambient = 1.0;
+        c.set_material(m.clone());
+        assert_eq!(&m, c.material());
+        assert_eq!(&Material::default(), c.left().material());
+        assert_eq!(&Material::default(), c.right().material());
+    }
+}
@@ -0,0 +1,220 @@
+use crate::intersections::{Intersection, EPSILON};
+use crate::materials::Material;
+use crate::matrix::Matrix4;
+use crate::rays::Ray;
+use crate::shapes::Shape;
+use crate::transformations::Transform;
+use crate::tuple::{Point3, Vec3};
+
+// An axis aligned cube, extending from -1 to 1 on every axis in object space
+#[derive(Debug, PartialEq, Clone)]
+pub struct Cube {
+    pub transform: Transform,
+    pub material: Material,
+    parent_transform: Transform,
+}
+
+impl Default for Cube {
+    fn default() -> Cube {
+        Cube {
+            transform: Transform::identity(),
+            material: Material::default(),
+            parent_transform: Transform::identity(),
+        }
+    }
+}
+
+// Where the ray crosses the two planes of one slab, nearest first
+fn check_axis(origin: f64, direction: f64) -> (f64, f64) {
+    let tmin_numerator = -1.0 - origin;
+    let tmax_numerator = 1.0 - origin;
+    let (tmin, tmax) = if direction.abs() >= EPSILON {
+        (tmin_numerator / direction, tmax_numerator / direction)
+    } else {
+        (
+            tmin_numerator * f64::INFINITY,
+            tmax_numerator * f64::INFINITY,
+        )
+    };
+    if tmin > tmax {
+        (tmax, tmin)
+    } else {
+        (tmin, tmax)
+    }
+}
+
+impl Shape for Cube {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn set_transform(&mut self, t: Matrix4) {
+        self.transform = Transform::new(t);
+    }
+
+    fn parent_transform(&self) -> &Transform {
+        &self.parent_transform
+    }
+
+    fn set_parent_transform(&mut self, t: Matrix4) {
+        self.parent_transform = Transform::new(t);
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_material(&mut self, m: Material) {
+        self.material = m;
+    }
+
+    fn local_intersect(&self, ray: Ray) -> Vec<Intersection<'_>> {
+        let (xtmin, xtmax) = check_axis(ray.origin.0, ray.direction.0);
+        let (ytmin, ytmax) = check_axis(ray.origin.1, ray.direction.1);
+        let (ztmin, ztmax) = check_axis(ray.origin.2, ray.direction.2);
+
+        // The ray is inside the cube between the last slab entered and the first slab left
+        let tmin = xtmin.max(ytmin).max(ztmin);
+        let tmax = xtmax.min(ytmax).min(ztmax);
+        if tmin > tmax {
+            return Vec::new();
+        }
+        vec![Intersection::new(tmin, self), Intersection::new(tmax, self)]
+    }
+
+    fn local_normal_at(&self, object_point: Point3) -> Vec3 {
+        // The face is given by the component with the largest absolute value
+        let Point3(x, y, z) = object_point;
+        let maxc = x.abs().max(y.abs()).max(z.abs());
+        if maxc == x.abs() {
+            Vec3::new(x, 0.0, 0.0)
+        } else if maxc == y.abs() {
+            Vec3::new(0.0, y, 0.0)
+        } else {
+            Vec3::new(0.0, 0.0, z)
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+
+mod tests {
+    use super::*;
+    use crate::transformations::{scaling, translation};
+
+    #[test]
+    fn a_ray_intersects_a_cube() {
+        let cases = vec![
+            // +x, -x, +y, -y, +z, -z and inside
+            (
+                Point3::new(5.0, 0.5, 0.0),
+                Vec3::new(-1.0, 0.0, 0.0),
+                4.0,
+                6.0,
+            ),
+            (
+                Point3::new(-5.0, 0.5, 0.0),
+                Vec3::new(1.0, 0.0, 0.0),
+                4.0,
+                6.0,
+            ),
+            (
+                Point3::new(0.5, 5.0, 0.0),
+                Vec3::new(0.0, -1.0, 0.0),
+                4.0,
+                6.0,
+            ),
+            (
+                Point3::new(0.5, -5.0, 0.0),
+                Vec3::new(0.0, 1.0, 0.0),
+                4.0,
+                6.0,
+            ),
+            (
+                Point3::new(0.5, 0.0, 5.0),
+                Vec3::new(0.0, 0.0, -1.0),
+                4.0,
+                6.0,
+            ),
+            (
+                Point3::new(0.5, 0.0, -5.0),
+                Vec3::new(0.0, 0.0, 1.0),
+                4.0,
+                6.0,
+            ),
+            (
+                Point3::new(0.0, 0.5, 0.0),
+                Vec3::new(0.0, 0.0, 1.0),
+                -1.0,
+                1.0,
+            ),
+        ];
+        let c = Cube::default();
+        for (origin, direction, t1, t2) in cases {
+            let xs = c.local_intersect(Ray::new(origin, direction));
+            assert_eq!(2, xs.len());
+            assert_eq!(t1, xs[0].t);
+            assert_eq!(t2, xs[1].t);
+        }
+    }
+    #[test]
+    fn a_ray_misses_a_cube() {
+        let cases = vec![
+            (
+                Point3::new(-2.0, 0.0, 0.0),
+                Vec3::new(0.2673, 0.5345, 0.8018),
+            ),
+            (
+                Point3::new(0.0, -2.0, 0.0),
+                Vec3::new(0.8018, 0.2673, 0.5345),
+            ),
+            (
+                Point3::new(0.0, 0.0, -2.0),
+                Vec3::new(0.5345, 0.8018, 0.2673),
+            ),
+            (Point3::new(2.0, 0.0, 2.0), Vec3::new(0.0, 0.0, -1.0)),
+            (Point3::new(0.0, 2.0, 2.0), Vec3::new(0.0, -1.0, 0.0)),
+            (Point3::new(2.0, 2.0, 0.0), Vec3::new(-1.0, 0.0, 0.0)),
+        ];
+        let c = Cube::default();
+        for (origin, direction) in cases {
+            let xs = c.local_intersect(Ray::new(origin, direction));
+            assert_eq!(0, xs.len());
+        }
+    }
+    #[test]
+    fn the_normal_on_the_surface_of_a_cube() {
+        let cases = vec![
+            (Point3::new(1.0, 0.5, -0.8), Vec3::new(1.0, 0.0, 0.0)),
+            (Point3::new(-1.0, -0.2, 0.9), Vec3::new(-1.0, 0.0, 0.0)),
+            (Point3::new(-0.4, 1.0, -0.1), Vec3::new(0.0, 1.0, 0.0)),
+            (Point3::new(0.3, -1.0, -0.7), Vec3::new(0.0, -1.0, 0.0)),
+            (Point3::new(-0.6, 0.3, 1.0), Vec3::new(0.0, 0.0, 1.0)),
+            (Point3::new(0.4, 0.4, -1.0), Vec3::new(0.0, 0.0, -1.0)),
+            (Point3::new(1.0, 1.0, 1.0), Vec3::new(1.0, 0.0, 0.0)),
+            (Point3::new(-1.0, -1.0, -1.0), Vec3::new(-1.0, 0.0, 0.0)),
+        ];
+        let c = Cube::default();
+        for (point, expected) in cases {
+            assert_eq!(expected, c.local_normal_at(point));
+        }
+    }
+    #[test]
+    fn intersecting_a_transformed_cube() {
+        let mut c = Cube::default();
+        c.set_transform(translation(0.0, 1.0, 0.0) * scaling(2.0, 1.0, 2.0));
+        let r = Ray::new(Point3::new(0.0, 1.5, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let xs = c.intersect(r);
+        assert_eq!(2, xs.len());
+        assert_eq!(3.0, xs[0].t);
+        assert_eq!(7.0, xs[1].t);
+    }
+    #[test]
+    fn the_normal_on_a_transformed_cube() {
+        let mut c = Cube::default();
+        c.set_transform(scaling(2.0, 1.0, 2.0));
+        let n = c.normal_at(Point3::new(0.0, 1.0, 0.5));
+        assert_eq!(Vec3::new(0.0, 1.0, 0.0), n);
+    }
+}
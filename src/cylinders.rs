@@ -0,0 +1,255 @@
+use crate::intersections::{Intersection, EPSILON};
+use crate::materials::Material;
+use crate::matrix::Matrix4;
+use crate::rays::Ray;
+use crate::shapes::Shape;
+use crate::transformations::Transform;
+use crate::tuple::{Point3, Vec3};
+
+// A cylinder of radius 1 around the y axis, optionally truncated and capped
+#[derive(Debug, PartialEq, Clone)]
+pub struct Cylinder {
+    pub transform: Transform,
+    pub material: Material,
+    parent_transform: Transform,
+    pub minimum: f64,
+    pub maximum: f64,
+    pub closed: bool,
+}
+
+impl Default for Cylinder {
+    fn default() -> Cylinder {
+        Cylinder {
+            transform: Transform::identity(),
+            material: Material::default(),
+            parent_transform: Transform::identity(),
+            minimum: f64::NEG_INFINITY,
+            maximum: f64::INFINITY,
+            closed: false,
+        }
+    }
+}
+
+impl Cylinder {
+    fn intersect_caps<'a>(&'a self, ray: &Ray, xs: &mut Vec<Intersection<'a>>) {
+        // Caps only matter if the cylinder is closed and might be hit
+        if !self.closed || ray.direction.1.abs() < EPSILON {
+            return;
+        }
+        for y in [self.minimum, self.maximum].iter() {
+            let t = (y - ray.origin.1) / ray.direction.1;
+            if check_cap(ray, t) {
+                xs.push(Intersection::new(t, self));
+            }
+        }
+    }
+}
+
+// Is the intersection at t within the radius (1) of the cylinder
+fn check_cap(ray: &Ray, t: f64) -> bool {
+    let x = ray.origin.0 + t * ray.direction.0;
+    let z = ray.origin.2 + t * ray.direction.2;
+    (x * x + z * z) <= 1.0
+}
+
+impl Shape for Cylinder {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn set_transform(&mut self, t: Matrix4) {
+        self.transform = Transform::new(t);
+    }
+
+    fn parent_transform(&self) -> &Transform {
+        &self.parent_transform
+    }
+
+    fn set_parent_transform(&mut self, t: Matrix4) {
+        self.parent_transform = Transform::new(t);
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_material(&mut self, m: Material) {
+        self.material = m;
+    }
+
+    fn local_intersect(&self, ray: Ray) -> Vec<Intersection<'_>> {
+        let mut xs = Vec::new();
+        let a = ray.direction.0 * ray.direction.0 + ray.direction.2 * ray.direction.2;
+
+        // A ray parallel to the y axis can only hit the caps
+        if a.abs() >= EPSILON {
+            let b = 2.0 * ray.origin.0 * ray.direction.0 + 2.0 * ray.origin.2 * ray.direction.2;
+            let c = ray.origin.0 * ray.origin.0 + ray.origin.2 * ray.origin.2 - 1.0;
+            let disc = b * b - 4.0 * a * c;
+            if disc < 0.0 {
+                return xs;
+            }
+            let mut t0 = (-b - disc.sqrt()) / (2.0 * a);
+            let mut t1 = (-b + disc.sqrt()) / (2.0 * a);
+            if t0 > t1 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            for t in [t0, t1].iter() {
+                let y = ray.origin.1 + t * ray.direction.1;
+                if self.minimum < y && y < self.maximum {
+                    xs.push(Intersection::new(*t, self));
+                }
+            }
+        }
+        self.intersect_caps(&ray, &mut xs);
+        xs
+    }
+
+    fn local_normal_at(&self, object_point: Point3) -> Vec3 {
+        let Point3(x, y, z) = object_point;
+        let dist = x * x + z * z;
+        if dist < 1.0 && y >= self.maximum - EPSILON {
+            Vec3::new(0.0, 1.0, 0.0)
+        } else if dist < 1.0 && y <= self.minimum + EPSILON {
+            Vec3::new(0.0, -1.0, 0.0)
+        } else {
+            Vec3::new(x, 0.0, z)
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_ray_misses_a_cylinder() {
+        let cases = vec![
+            (Point3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0)),
+            (Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0)),
+            (Point3::new(0.0, 0.0, -5.0), Vec3::new(1.0, 1.0, 1.0)),
+        ];
+        let cyl = Cylinder::default();
+        for (origin, direction) in cases {
+            let r = Ray::new(origin, direction.normalize());
+            assert_eq!(0, cyl.local_intersect(r).len());
+        }
+    }
+    #[test]
+    fn a_ray_strikes_a_cylinder() {
+        let cases = vec![
+            (
+                Point3::new(1.0, 0.0, -5.0),
+                Vec3::new(0.0, 0.0, 1.0),
+                5.0,
+                5.0,
+            ),
+            (
+                Point3::new(0.0, 0.0, -5.0),
+                Vec3::new(0.0, 0.0, 1.0),
+                4.0,
+                6.0,
+            ),
+            (
+                Point3::new(0.5, 0.0, -5.0),
+                Vec3::new(0.1, 1.0, 1.0),
+                6.80798,
+                7.08872,
+            ),
+        ];
+        let cyl = Cylinder::default();
+        for (origin, direction, t0, t1) in cases {
+            let r = Ray::new(origin, direction.normalize());
+            let xs = cyl.local_intersect(r);
+            assert_eq!(2, xs.len());
+            assert!((t0 - xs[0].t).abs() < EPSILON);
+            assert!((t1 - xs[1].t).abs() < EPSILON);
+        }
+    }
+    #[test]
+    fn normal_vector_on_a_cylinder() {
+        let cases = vec![
+            (Point3::new(1.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0)),
+            (Point3::new(0.0, 5.0, -1.0), Vec3::new(0.0, 0.0, -1.0)),
+            (Point3::new(0.0, -2.0, 1.0), Vec3::new(0.0, 0.0, 1.0)),
+            (Point3::new(-1.0, 1.0, 0.0), Vec3::new(-1.0, 0.0, 0.0)),
+        ];
+        let cyl = Cylinder::default();
+        for (point, normal) in cases {
+            assert_eq!(normal, cyl.local_normal_at(point));
+        }
+    }
+    #[test]
+    fn the_default_minimum_and_maximum_for_a_cylinder() {
+        let cyl = Cylinder::default();
+        assert_eq!(f64::NEG_INFINITY, cyl.minimum);
+        assert_eq!(f64::INFINITY, cyl.maximum);
+    }
+    #[test]
+    fn intersecting_a_constrained_cylinder() {
+        let cases = vec![
+            (Point3::new(0.0, 1.5, 0.0), Vec3::new(0.1, 1.0, 0.0), 0),
+            (Point3::new(0.0, 3.0, -5.0), Vec3::new(0.0, 0.0, 1.0), 0),
+            (Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0), 0),
+            (Point3::new(0.0, 2.0, -5.0), Vec3::new(0.0, 0.0, 1.0), 0),
+            (Point3::new(0.0, 1.0, -5.0), Vec3::new(0.0, 0.0, 1.0), 0),
+            (Point3::new(0.0, 1.5, -2.0), Vec3::new(0.0, 0.0, 1.0), 2),
+        ];
+        let cyl = Cylinder {
+            minimum: 1.0,
+            maximum: 2.0,
+            ..Cylinder::default()
+        };
+        for (point, direction, count) in cases {
+            let r = Ray::new(point, direction.normalize());
+            assert_eq!(count, cyl.local_intersect(r).len());
+        }
+    }
+    #[test]
+    fn the_default_closed_value_for_a_cylinder() {
+        let cyl = Cylinder::default();
+        assert!(!cyl.closed);
+    }
+    #[test]
+    fn intersecting_the_caps_of_a_closed_cylinder() {
+        let cases = vec![
+            (Point3::new(0.0, 3.0, 0.0), Vec3::new(0.0, -1.0, 0.0), 2),
+            (Point3::new(0.0, 3.0, -2.0), Vec3::new(0.0, -1.0, 2.0), 2),
+            (Point3::new(0.0, 4.0, -2.0), Vec3::new(0.0, -1.0, 1.0), 2),
+            (Point3::new(0.0, 0.0, -2.0), Vec3::new(0.0, 1.0, 2.0), 2),
+            (Point3::new(0.0, -1.0, -2.0), Vec3::new(0.0, 1.0, 1.0), 2),
+        ];
+        let cyl = Cylinder {
+            minimum: 1.0,
+            maximum: 2.0,
+            closed: true,
+            ..Cylinder::default()
+        };
+        for (point, direction, count) in cases {
+            let r = Ray::new(point, direction.normalize());
+            assert_eq!(count, cyl.local_intersect(r).len());
+        }
+    }
+    #[test]
+    fn the_normal_vector_on_a_cylinders_end_caps() {
+        let cases = vec![
+            (Point3::new(0.0, 1.0, 0.0), Vec3::new(0.0, -1.0, 0.0)),
+            (Point3::new(0.5, 1.0, 0.0), Vec3::new(0.0, -1.0, 0.0)),
+            (Point3::new(0.0, 1.0, 0.5), Vec3::new(0.0, -1.0, 0.0)),
+            (Point3::new(0.0, 2.0, 0.0), Vec3::new(0.0, 1.0, 0.0)),
+            (Point3::new(0.5, 2.0, 0.0), Vec3::new(0.0, 1.0, 0.0)),
+            (Point3::new(0.0, 2.0, 0.5), Vec3::new(0.0, 1.0, 0.0)),
+        ];
+        let cyl = Cylinder {
+            minimum: 1.0,
+            maximum: 2.0,
+            closed: true,
+            ..Cylinder::default()
+        };
+        for (point, normal) in cases {
+            assert_eq!(normal, cyl.local_normal_at(point));
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use crate::intersections::{intersections, Intersection};
+use crate::materials::Material;
+use crate::matrix::Matrix4;
+use crate::rays::Ray;
+use crate::shapes::{same_shape, Shape, SingularTransform};
+use crate::transformations::Transform;
+use crate::tuple::{Point3, Vec3};
+
+// A collection of shapes transformed as one unit. Changing the transform of
+// the group is passed on to all children as their parent transform.
+#[derive(Debug)]
+pub struct Group {
+    transform: Transform,
+    parent_transform: Transform,
+    material: Material,
+    // Once a material is set it is given to every child, including the ones
+    // added later, so the result does not depend on the order of the calls
+    shares_material: bool,
+    children: Vec<Box<dyn Shape>>,
+}
+
+impl Default for Group {
+    fn default() -> Group {
+        Group {
+            transform: Transform::identity(),
+            parent_transform: Transform::identity(),
+            material: Material::default(),
+            shares_material: false,
+            children: Vec::new(),
+        }
+    }
+}
+
+impl Group {
+    pub fn children(&self) -> &[Box<dyn Shape>] {
+        &self.children
+    }
+
+    pub fn add_child(&mut self, mut child: Box<dyn Shape>) {
+        child.set_parent_transform(self.world_transform());
+        if self.shares_material {
+            child.set_material(self.material.clone());
+        }
+        self.children.push(child);
+    }
+
+    fn world_transform(&self) -> Matrix4 {
+        self.parent_transform.matrix() * self.transform.matrix()
+    }
+
+    fn update_children(&mut self) {
+        let t = self.world_transform();
+        for child in self.children.iter_mut() {
+            child.set_parent_transform(t);
+        }
+    }
+}
+
+impl Shape for Group {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn set_transform(&mut self, t: Matrix4) {
+        self.transform = Transform::new(t);
+        self.update_children();
+    }
+
+    fn parent_transform(&self) -> &Transform {
+        &self.parent_transform
+    }
+
+    fn set_parent_transform(&mut self, t: Matrix4) {
+        self.parent_transform = Transform::new(t);
+        self.update_children();
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    // The material is applied to all children, e.g. all triangles of a mesh
+    fn set_material(&mut self, m: Material) {
+        for child in self.children.iter_mut() {
+            child.set_material(m.clone());
+        }
+        self.material = m;
+        self.shares_material = true;
+    }
+
+    fn local_intersect(&self, ray: Ray) -> Vec<Intersection<'_>> {
+        let xs = self
+            .children
+            .iter()
+            .flat_map(|child| child.intersect(ray.clone()))
+            .collect();
+        intersections(xs)
+    }
+
+    fn local_normal_at(&self, _object_point: Point3) -> Vec3 {
+        unreachable!("A group has no surface, normals are computed on its children")
+    }
+
+    fn check_transform(&self, path: &str) -> Result<(), SingularTransform> {
+        self.own_transform_check(path)?;
+        self.children
+            .iter()
+            .enumerate()
+            .try_for_each(|(i, child)| child.check_transform(&format!("{}.children[{}]", path, i)))
+    }
+
+    fn includes(&self, other: &dyn Shape) -> bool {
+        same_shape(self, other) || self.children.iter().any(|child| child.includes(other))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+
+mod tests {
+    use super::*;
+    use crate::spheres::Sphere;
+    use crate::transformations::{rotation_y, scaling, translation};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn creating_a_new_group() {
+        let g = Group::default();
+        assert_eq!(&Matrix4::identity(), g.transform());
+        assert!(g.children().is_empty());
+    }
+    #[test]
+    fn adding_a_child_to_a_group() {
+        let mut g = Group::default();
+        g.set_transform(translation(1.0, 0.0, 0.0));
+        g.add_child(Box::new(Sphere::default()));
+        assert_eq!(1, g.children().len());
+        assert_eq!(
+            &translation(1.0, 0.0, 0.0),
+            g.children()[0].parent_transform()
+        );
+    }
+    #[test]
+    fn intersecting_a_ray_with_an_empty_group() {
+        let g = Group::default();
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        assert!(g.local_intersect(r).is_empty());
+    }
+    #[test]
+    fn intersecting_a_ray_with_a_nonempty_group() {
+        let mut g = Group::default();
+        let s1 = Sphere::default();
+        let mut s2 = Sphere::default();
+        s2.set_transform(translation(0.0, 0.0, -3.0));
+        let mut s3 = Sphere::default();
+        s3.set_transform(translation(5.0, 0.0, 0.0));
+        g.add_child(Box::new(s1));
+        g.add_child(Box::new(s2));
+        g.add_child(Box::new(s3));
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let xs = g.local_intersect(r);
+        let s1 = g.children()[0].as_ref();
+        let s2 = g.children()[1].as_ref();
+        assert_eq!(4, xs.len());
+        assert!(same_shape(s2, xs[0].object));
+        assert!(same_shape(s2, xs[1].object));
+        assert!(same_shape(s1, xs[2].object));
+        assert!(same_shape(s1, xs[3].object));
+    }
+    #[test]
+    fn intersecting_a_transformed_group() {
+        let mut g = Group::default();
+        g.set_transform(scaling(2.0, 2.0, 2.0));
+        let mut s = Sphere::default();
+        s.set_transform(translation(5.0, 0.0, 0.0));
+        g.add_child(Box::new(s));
+        let r = Ray::new(Point3::new(10.0, 0.0, -10.0), Vec3::new(0.0, 0.0, 1.0));
+        let xs = g.intersect(r);
+        assert_eq!(2, xs.len());
+    }
+    #[test]
+    fn nested_groups_pass_their_transforms_to_all_descendants() {
+        let mut g1 = Group::default();
+        g1.set_transform(rotation_y(PI / 2.0));
+        let mut g2 = Group::default();
+        g2.set_transform(scaling(1.0, 2.0, 3.0));
+        let mut s = Sphere::default();
+        s.set_transform(translation(5.0, 0.0, 0.0));
+        g2.add_child(Box::new(s));
+        g1.add_child(Box::new(g2));
+
+        // The ray hits the sphere at (1.7321, 1.1547, -5.5774) in world space
+        let r = Ray::new(Point3::new(1.7321, 1.1547, -10.0), Vec3::new(0.0, 0.0, 1.0));
+        let xs = g1.intersect(r.clone());
+        assert_eq!(2, xs.len());
+        let s = xs[0].object;
+        assert_eq!(
+            &(rotation_y(PI / 2.0) * scaling(1.0, 2.0, 3.0)),
+            s.parent_transform()
+        );
+        let n = s.normal_at(Point3::new(1.7321, 1.1547, -5.5774));
+        assert_eq!(Vec3::new(0.28570, 0.42854, -0.85716), n);
+    }
+    #[test]
+    fn changing_the_transform_of_a_group_updates_its_children() {
+        let mut g = Group::default();
+        g.add_child(Box::new(Sphere::default()));
+        g.set_transform(translation(0.0, 2.0, 0.0));
+        assert_eq!(
+            &translation(0.0, 2.0, 0.0),
+            g.children()[0].parent_transform()
+        );
+    }
+    #[test]
+    fn setting_the_material_of_a_group_sets_it_on_its_children() {
+        let mut g = Group::default();
+        g.add_child(Box::new(Sphere::default()));
+        let mut m = Material::default();
+        m.ambient = 1.0;
+        g.set_material(m.clone());
+        assert_eq!(&m, g.children()[0].material());
+    }
+    #[test]
+    fn a_child_added_after_the_material_is_set_gets_it_too() {
+        let mut g = Group::default();
+        let mut m = Material::default();
+        m.ambient = 1.0;
+        g.set_material(m.clone());
+        g.add_child(Box::new(Sphere::default()));
+        assert_eq!(&m, g.children()[0].material());
+    }
+    #[test]
+    fn children_keep_their_material_until_one_is_set_on_the_group() {
+        let mut s = Sphere::default();
+        let mut m = Material::default();
+        m.ambient = 1.0;
+        s.set_material(m.clone());
+        let mut g = Group::default();
+        g.add_child(Box::new(s));
+        assert_eq!(&m, g.children()[0].material());
+    }
+    #[test]
+    fn a_group_includes_its_descendants() {
+        let mut g1 = Group::default();
+        let mut g2 = Group::default();
+        g2.add_child(Box::new(Sphere::default()));
+        g1.add_child(Box::new(g2));
+        // The only way to reach the nested sphere is through a hit
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let xs = g1.intersect(r);
+        assert!(g1.includes(xs[0].object));
+        assert!(g1.includes(&g1));
+        assert!(!g1.includes(&Sphere::default()));
+    }
+}
@@ -1,16 +1,135 @@
-use crate::spheres::Sphere;
+use crate::rays::Ray;
+use crate::shapes::{same_shape, Shape};
+use crate::tuple::{Point3, Vec3};
 use std::cmp::Ordering;
 
-#[derive(Debug, PartialEq, Clone)]
+// Used to nudge points off a surface to avoid self intersection (acne)
+pub const EPSILON: f64 = 1e-5;
+
+#[derive(Debug, Clone)]
 pub struct Intersection<'a> {
     pub t: f64,
-    pub object: &'a Sphere,
+    pub object: &'a dyn Shape,
+    // Where on the surface the hit is, only used by shapes that interpolate normals
+    pub u: f64,
+    pub v: f64,
 }
 
 impl<'a> Intersection<'a> {
-    pub fn new(tt: f64, oo: &'a Sphere) -> Intersection<'a> {
-        Intersection { t: tt, object: oo }
+    pub fn new(tt: f64, oo: &'a dyn Shape) -> Intersection<'a> {
+        Intersection::with_uv(tt, oo, 0.0, 0.0)
+    }
+    pub fn with_uv(tt: f64, oo: &'a dyn Shape, u: f64, v: f64) -> Intersection<'a> {
+        Intersection {
+            t: tt,
+            object: oo,
+            u,
+            v,
+        }
+    }
+}
+
+impl<'a> PartialEq for Intersection<'a> {
+    fn eq(&self, other: &Intersection) -> bool {
+        self.t == other.t && same_shape(self.object, other.object)
+    }
+}
+
+// The state of a hit, precomputed once and shared by all shading code
+#[derive(Debug, Clone)]
+pub struct Computations<'a> {
+    pub t: f64,
+    pub object: &'a dyn Shape,
+    pub point: Point3,
+    pub over_point: Point3,
+    // Just below the surface, where refracted rays start
+    pub under_point: Point3,
+    pub eyev: Vec3,
+    pub normalv: Vec3,
+    pub reflectv: Vec3,
+    pub inside: bool,
+    // Refractive indices of the materials the ray leaves and enters
+    pub n1: f64,
+    pub n2: f64,
+}
+
+// xs are all the intersections of the ray, sorted, and are needed to find
+// which objects contain the hit for the refractive indices
+pub fn prepare_computations<'a>(
+    i: &Intersection<'a>,
+    r: &Ray,
+    xs: &[Intersection],
+) -> Computations<'a> {
+    let point = r.position(i.t);
+    let eyev = -r.direction;
+    let mut normalv = i.object.normal_at_hit(point, i);
+    // The normal must point against the eye, so flip it when the ray starts inside the object
+    let inside = normalv.dot(eyev) < 0.0;
+    if inside {
+        normalv = -normalv;
     }
+    let (n1, n2) = refractive_indices(i, xs);
+    Computations {
+        t: i.t,
+        object: i.object,
+        point,
+        over_point: point + normalv * EPSILON,
+        under_point: point - normalv * EPSILON,
+        eyev,
+        normalv,
+        reflectv: r.direction.reflect(normalv),
+        inside,
+        n1,
+        n2,
+    }
+}
+
+// Walks the intersections keeping a list of the objects the ray is currently inside.
+// At the hit, the last object entered gives n1 before and n2 after the toggle.
+fn refractive_indices(hit: &Intersection, xs: &[Intersection]) -> (f64, f64) {
+    let mut containers: Vec<&dyn Shape> = Vec::new();
+    let mut n1 = 1.0;
+    for i in xs {
+        if i == hit {
+            n1 = containers
+                .last()
+                .map_or(1.0, |object| object.material().refractive_index);
+        }
+        match containers
+            .iter()
+            .position(|&object| same_shape(object, i.object))
+        {
+            Some(index) => {
+                containers.remove(index);
+            }
+            None => containers.push(i.object),
+        }
+        if i == hit {
+            let n2 = containers
+                .last()
+                .map_or(1.0, |object| object.material().refractive_index);
+            return (n1, n2);
+        }
+    }
+    (n1, 1.0)
+}
+
+// Schlick's approximation of the Fresnel effect - the fraction of light
+// reflected, growing from the head-on value toward 1 at grazing angles
+pub fn schlick(comps: &Computations) -> f64 {
+    let mut cos = comps.eyev.dot(comps.normalv);
+    // Going into a less dense material total internal reflection is possible
+    if comps.n1 > comps.n2 {
+        let n = comps.n1 / comps.n2;
+        let sin2_t = n.powi(2) * (1.0 - cos.powi(2));
+        if sin2_t > 1.0 {
+            return 1.0;
+        }
+        // Use the angle of the transmitted ray instead
+        cos = (1.0 - sin2_t).sqrt();
+    }
+    let r0 = ((comps.n1 - comps.n2) / (comps.n1 + comps.n2)).powi(2);
+    r0 + (1.0 - r0) * (1.0 - cos).powi(5)
 }
 
 pub fn intersections(xs: Vec<Intersection>) -> Vec<Intersection> {
@@ -30,12 +149,25 @@ pub fn hit(xs: Vec<Intersection>) -> Option<Intersection> {
 
 mod tests {
     use super::*;
+    use crate::planes::Plane;
+    use crate::shapes::Shape;
+    use crate::spheres::Sphere;
+    use crate::transformations::{scaling, translation};
+
     #[test]
     fn an_intersection_encapsulates_t_and_an_object() {
         let s = Sphere::default();
         let i = Intersection::new(3.5, &s);
         assert_eq!(3.5, i.t);
-        assert_eq!(&s, i.object);
+        assert!(same_shape(&s, i.object));
+    }
+
+    #[test]
+    fn an_intersection_can_encapsulate_u_and_v() {
+        let s = Sphere::default();
+        let i = Intersection::with_uv(3.5, &s, 0.2, 0.4);
+        assert_eq!(0.2, i.u);
+        assert_eq!(0.4, i.v);
     }
 
     #[test]
@@ -86,4 +218,139 @@ mod tests {
         let i = hit(xs);
         assert_eq!(Some(i4), i);
     }
+    #[test]
+    fn precomputing_the_state_of_an_intersection() {
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let shape = Sphere::default();
+        let i = Intersection::new(4.0, &shape);
+        let comps = prepare_computations(&i, &r, std::slice::from_ref(&i));
+        assert_eq!(i.t, comps.t);
+        assert!(same_shape(i.object, comps.object));
+        assert_eq!(Point3::new(0.0, 0.0, -1.0), comps.point);
+        assert_eq!(Vec3::new(0.0, 0.0, -1.0), comps.eyev);
+        assert_eq!(Vec3::new(0.0, 0.0, -1.0), comps.normalv);
+    }
+    #[test]
+    fn the_hit_when_an_intersection_occurs_on_the_outside() {
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let shape = Sphere::default();
+        let i = Intersection::new(4.0, &shape);
+        let comps = prepare_computations(&i, &r, std::slice::from_ref(&i));
+        assert!(!comps.inside);
+    }
+    #[test]
+    fn the_hit_when_an_intersection_occurs_on_the_inside() {
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        let shape = Sphere::default();
+        let i = Intersection::new(1.0, &shape);
+        let comps = prepare_computations(&i, &r, std::slice::from_ref(&i));
+        assert_eq!(Point3::new(0.0, 0.0, 1.0), comps.point);
+        assert_eq!(Vec3::new(0.0, 0.0, -1.0), comps.eyev);
+        assert!(comps.inside);
+        // normal would have been (0, 0, 1), but is inverted
+        assert_eq!(Vec3::new(0.0, 0.0, -1.0), comps.normalv);
+    }
+    #[test]
+    fn the_hit_should_offset_the_point() {
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let mut shape = Sphere::default();
+        shape.set_transform(translation(0.0, 0.0, 1.0));
+        let i = Intersection::new(5.0, &shape);
+        let comps = prepare_computations(&i, &r, std::slice::from_ref(&i));
+        assert!(comps.over_point.2 < -EPSILON / 2.0);
+        assert!(comps.point.2 > comps.over_point.2);
+    }
+    #[test]
+    fn precomputing_the_reflection_vector() {
+        let shape = Plane::default();
+        let r = Ray::new(
+            Point3::new(0.0, 1.0, -1.0),
+            Vec3::new(0.0, -(2_f64.sqrt()) / 2.0, 2_f64.sqrt() / 2.0),
+        );
+        let i = Intersection::new(2_f64.sqrt(), &shape);
+        let comps = prepare_computations(&i, &r, std::slice::from_ref(&i));
+        assert_eq!(
+            Vec3::new(0.0, 2_f64.sqrt() / 2.0, 2_f64.sqrt() / 2.0),
+            comps.reflectv
+        );
+    }
+    #[test]
+    fn finding_n1_and_n2_at_various_intersections() {
+        let mut a = Sphere::glass();
+        a.set_transform(scaling(2.0, 2.0, 2.0));
+        a.material.refractive_index = 1.5;
+        let mut b = Sphere::glass();
+        b.set_transform(translation(0.0, 0.0, -0.25));
+        b.material.refractive_index = 2.0;
+        let mut c = Sphere::glass();
+        c.set_transform(translation(0.0, 0.0, 0.25));
+        c.material.refractive_index = 2.5;
+        let r = Ray::new(Point3::new(0.0, 0.0, -4.0), Vec3::new(0.0, 0.0, 1.0));
+        let xs = intersections(vec![
+            Intersection::new(2.0, &a),
+            Intersection::new(2.75, &b),
+            Intersection::new(3.25, &c),
+            Intersection::new(4.75, &b),
+            Intersection::new(5.25, &c),
+            Intersection::new(6.0, &a),
+        ]);
+        let expected = [
+            (1.0, 1.5),
+            (1.5, 2.0),
+            (2.0, 2.5),
+            (2.5, 2.5),
+            (2.5, 1.5),
+            (1.5, 1.0),
+        ];
+        for (i, &(n1, n2)) in expected.iter().enumerate() {
+            let comps = prepare_computations(&xs[i], &r, &xs);
+            assert_eq!(n1, comps.n1);
+            assert_eq!(n2, comps.n2);
+        }
+    }
+    #[test]
+    fn the_under_point_is_offset_below_the_surface() {
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let mut shape = Sphere::glass();
+        shape.set_transform(translation(0.0, 0.0, 1.0));
+        let i = Intersection::new(5.0, &shape);
+        let xs = intersections(vec![i.clone()]);
+        let comps = prepare_computations(&i, &r, &xs);
+        assert!(comps.under_point.2 > EPSILON / 2.0);
+        assert!(comps.point.2 < comps.under_point.2);
+    }
+    #[test]
+    fn the_schlick_approximation_under_total_internal_reflection() {
+        let shape = Sphere::glass();
+        let r = Ray::new(
+            Point3::new(0.0, 0.0, 2_f64.sqrt() / 2.0),
+            Vec3::new(0.0, 1.0, 0.0),
+        );
+        let xs = intersections(vec![
+            Intersection::new(-(2_f64.sqrt()) / 2.0, &shape),
+            Intersection::new(2_f64.sqrt() / 2.0, &shape),
+        ]);
+        let comps = prepare_computations(&xs[1], &r, &xs);
+        assert_eq!(1.0, schlick(&comps));
+    }
+    #[test]
+    fn the_schlick_approximation_with_a_perpendicular_viewing_angle() {
+        let shape = Sphere::glass();
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+        let xs = intersections(vec![
+            Intersection::new(-1.0, &shape),
+            Intersection::new(1.0, &shape),
+        ]);
+        let comps = prepare_computations(&xs[1], &r, &xs);
+        assert!((0.04 - schlick(&comps)).abs() < EPSILON);
+    }
+    #[test]
+    fn the_schlick_approximation_with_small_angle_and_n2_greater_than_n1() {
+        let shape = Sphere::glass();
+        // Nearly grazing the top of the sphere
+        let r = Ray::new(Point3::new(0.0, 0.99, -2.0), Vec3::new(0.0, 0.0, 1.0));
+        let xs = intersections(vec![Intersection::new(1.8589, &shape)]);
+        let comps = prepare_computations(&xs[0], &r, &xs);
+        assert!((0.48873 - schlick(&comps)).abs() < EPSILON);
+    }
 }
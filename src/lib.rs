@@ -1,10 +1,25 @@
 pub mod file;
+pub mod camera;
 pub mod canvas;
+pub mod cones;
+pub mod csg;
+pub mod cubes;
+pub mod cylinders;
+pub mod groups;
 pub mod intersections;
 pub mod lights;
 pub mod materials;
 pub mod matrix;
+pub mod obj;
+pub mod patterns;
+pub mod perlin;
+pub mod planes;
 pub mod rays;
+pub mod shapes;
+mod simd;
 pub mod spheres;
+pub mod textures;
 pub mod transformations;
-pub mod tuple;
\ No newline at end of file
+pub mod triangles;
+pub mod tuple;
+pub mod world;
@@ -1,13 +1,53 @@
-use crate::tuple::Tuple;
+use crate::tuple::{Color, Point3, Vec3};
+use crate::world::World;
+use std::f64::consts::PI;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Where a light is seen from a point - the direction toward it and how far away it is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightSample {
+    pub direction: Vec3,
+    pub distance: f64,
+}
+
+// Common behaviour for all lights. Shading averages the diffuse and specular
+// parts over the samples, and shadows are tested toward each sample.
+pub trait Light: Debug + Send + Sync {
+    // The color and brightness of the light arriving at the point, ignoring shadows
+    fn intensity(&self, point: Point3) -> Color;
+    // Normalized, from the point toward the center of the light
+    fn direction_to_light(&self, point: Point3) -> Vec3;
+    // Infinite for lights without a position, like the sun
+    fn distance_to_light(&self, point: Point3) -> f64;
+
+    // Lights without a size are a single sample
+    fn samples(&self, point: Point3) -> Vec<LightSample> {
+        vec![LightSample {
+            direction: self.direction_to_light(point),
+            distance: self.distance_to_light(point),
+        }]
+    }
+
+    // The fraction of the samples that aren't shadowed from the point
+    fn intensity_at(&self, point: Point3, world: &World) -> f64 {
+        let samples = self.samples(point);
+        let lit = samples
+            .iter()
+            .filter(|sample| !world.is_occluded(point, sample.direction, sample.distance))
+            .count();
+        lit as f64 / samples.len() as f64
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct PointLight {
-    pub intensity: Tuple,
-    pub position: Tuple,
+    pub intensity: Color,
+    pub position: Point3,
 }
 
 impl PointLight {
-    pub fn new(p: Tuple, i: Tuple) -> PointLight {
+    pub fn new(p: Point3, i: Color) -> PointLight {
         PointLight {
             intensity: i,
             position: p,
@@ -15,17 +55,457 @@ impl PointLight {
     }
 }
 
+impl Light for PointLight {
+    fn intensity(&self, _point: Point3) -> Color {
+        self.intensity
+    }
+
+    fn direction_to_light(&self, point: Point3) -> Vec3 {
+        (self.position - point).normalize()
+    }
+
+    fn distance_to_light(&self, point: Point3) -> f64 {
+        (self.position - point).magnitude()
+    }
+}
+
+// Parallel light from infinitely far away, like the sun
+#[derive(Debug, Clone)]
+pub struct DirectionalLight {
+    pub intensity: Color,
+    // The direction the light travels in, normalized
+    pub direction: Vec3,
+}
+
+impl DirectionalLight {
+    pub fn new(direction: Vec3, intensity: Color) -> DirectionalLight {
+        DirectionalLight {
+            intensity,
+            direction: direction.normalize(),
+        }
+    }
+}
+
+impl Light for DirectionalLight {
+    fn intensity(&self, _point: Point3) -> Color {
+        self.intensity
+    }
+
+    fn direction_to_light(&self, _point: Point3) -> Vec3 {
+        -self.direction
+    }
+
+    fn distance_to_light(&self, _point: Point3) -> f64 {
+        f64::INFINITY
+    }
+}
+
+// A point light shining in a cone. Inside angle the light is at full
+// intensity, then it fades out linearly over the falloff angle.
+#[derive(Debug, Clone)]
+pub struct SpotLight {
+    pub intensity: Color,
+    pub position: Point3,
+    // The direction the cone points in, normalized
+    pub direction: Vec3,
+    // Both in radians, measured from the direction
+    pub angle: f64,
+    pub falloff: f64,
+}
+
+impl SpotLight {
+    pub fn new(
+        position: Point3,
+        direction: Vec3,
+        angle: f64,
+        falloff: f64,
+        intensity: Color,
+    ) -> SpotLight {
+        SpotLight {
+            intensity,
+            position,
+            direction: direction.normalize(),
+            angle,
+            falloff,
+        }
+    }
+}
+
+impl Light for SpotLight {
+    fn intensity(&self, point: Point3) -> Color {
+        let to_point = (point - self.position).normalize();
+        let angle = to_point.dot(self.direction).clamp(-1.0, 1.0).acos();
+        if angle <= self.angle {
+            self.intensity
+        } else if angle >= (self.angle + self.falloff).min(PI) {
+            Color::new(0.0, 0.0, 0.0)
+        } else {
+            self.intensity * (1.0 - (angle - self.angle) / self.falloff)
+        }
+    }
+
+    fn direction_to_light(&self, point: Point3) -> Vec3 {
+        (self.position - point).normalize()
+    }
+
+    fn distance_to_light(&self, point: Point3) -> f64 {
+        (self.position - point).magnitude()
+    }
+}
+
+// A cycling list of numbers between 0 and 1 used to jitter the samples of an
+// area light. Shared between render threads, so the position is atomic.
+// A serial render always draws the values in the same order and is
+// reproducible. With several threads the order depends on scheduling, so the
+// soft shadow noise can differ between runs. That is accepted, since every
+// sample still comes from the same fixed set of values.
+#[derive(Debug)]
+pub struct Sequence {
+    values: Vec<f64>,
+    index: AtomicUsize,
+}
+
+impl Sequence {
+    pub fn new(values: Vec<f64>) -> Sequence {
+        assert!(!values.is_empty());
+        Sequence {
+            values,
+            index: AtomicUsize::new(0),
+        }
+    }
+
+    // Pseudo random values from a xorshift generator, the same for every run
+    pub fn random(count: usize) -> Sequence {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let values = (0..count)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state >> 11) as f64 / (1u64 << 53) as f64
+            })
+            .collect();
+        Sequence::new(values)
+    }
+
+    pub fn next(&self) -> f64 {
+        let i = self.index.fetch_add(1, Ordering::Relaxed);
+        self.values[i % self.values.len()]
+    }
+}
+
+impl Clone for Sequence {
+    fn clone(&self) -> Sequence {
+        Sequence::new(self.values.clone())
+    }
+}
+
+// A rectangular light made of usteps * vsteps cells. Each cell is sampled at
+// a jittered point, so the shadows get soft edges.
+#[derive(Debug, Clone)]
+pub struct AreaLight {
+    pub corner: Point3,
+    // One cell along each edge
+    pub uvec: Vec3,
+    pub usteps: usize,
+    pub vvec: Vec3,
+    pub vsteps: usize,
+    pub samples: usize,
+    // The center of the light
+    pub position: Point3,
+    pub intensity: Color,
+    pub jitter_by: Sequence,
+}
+
+impl AreaLight {
+    pub fn new(
+        corner: Point3,
+        full_uvec: Vec3,
+        usteps: usize,
+        full_vvec: Vec3,
+        vsteps: usize,
+        intensity: Color,
+    ) -> AreaLight {
+        assert!(usteps > 0 && vsteps > 0);
+        AreaLight {
+            corner,
+            uvec: full_uvec / usteps as f64,
+            usteps,
+            vvec: full_vvec / vsteps as f64,
+            vsteps,
+            samples: usteps * vsteps,
+            position: corner + full_uvec / 2.0 + full_vvec / 2.0,
+            intensity,
+            jitter_by: Sequence::random(256),
+        }
+    }
+
+    // A point somewhere in the cell u, v
+    pub fn point_on_light(&self, u: usize, v: usize) -> Point3 {
+        self.corner
+            + self.uvec * (u as f64 + self.jitter_by.next())
+            + self.vvec * (v as f64 + self.jitter_by.next())
+    }
+
+    pub fn sample_points(&self) -> Vec<Point3> {
+        let mut points = Vec::with_capacity(self.samples);
+        for v in 0..self.vsteps {
+            for u in 0..self.usteps {
+                points.push(self.point_on_light(u, v));
+            }
+        }
+        points
+    }
+}
+
+impl Light for AreaLight {
+    fn intensity(&self, _point: Point3) -> Color {
+        self.intensity
+    }
+
+    fn direction_to_light(&self, point: Point3) -> Vec3 {
+        (self.position - point).normalize()
+    }
+
+    fn distance_to_light(&self, point: Point3) -> f64 {
+        (self.position - point).magnitude()
+    }
+
+    // One jittered sample in each cell
+    fn samples(&self, point: Point3) -> Vec<LightSample> {
+        self.sample_points()
+            .into_iter()
+            .map(|sample| LightSample {
+                direction: (sample - point).normalize(),
+                distance: (sample - point).magnitude(),
+            })
+            .collect()
+    }
+}
+
+// A point light is an area light of a single cell with no extent
+impl From<PointLight> for AreaLight {
+    fn from(light: PointLight) -> AreaLight {
+        let none = Vec3::new(0.0, 0.0, 0.0);
+        AreaLight::new(light.position, none, 1, none, 1, light.intensity)
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::float_cmp)]
+
 mod tests {
     use super::*;
-    use crate::tuple::Tuple;
+    use crate::tuple::{Color, Point3, Vec3};
+
+    fn area_light() -> AreaLight {
+        AreaLight::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            4,
+            Vec3::new(0.0, 0.0, 1.0),
+            2,
+            Color::new(1.0, 1.0, 1.0),
+        )
+    }
 
     #[test]
     fn a_point_light_has_a_position_and_intensity() {
-        let intensity = Tuple::color(1.0, 1.0, 1.0);
-        let position = Tuple::point(0.0, 0.0, 0.0);
+        let intensity = Color::new(1.0, 1.0, 1.0);
+        let position = Point3::new(0.0, 0.0, 0.0);
         let light = PointLight::new(position, intensity);
         assert_eq!(intensity, light.intensity);
         assert_eq!(position, light.position);
     }
+    #[test]
+    fn point_lights_evaluate_the_light_intensity_at_a_given_point() {
+        let w = World::default();
+        let light = &w.lights[0];
+        let expected = [
+            (Point3::new(0.0, 1.0001, 0.0), 1.0),
+            (Point3::new(-1.0001, 0.0, 0.0), 1.0),
+            (Point3::new(0.0, 0.0, -1.0001), 1.0),
+            (Point3::new(0.0, 0.0, 1.0001), 0.0),
+            (Point3::new(1.0001, 0.0, 0.0), 0.0),
+            (Point3::new(0.0, -1.0001, 0.0), 0.0),
+            (Point3::new(0.0, 0.0, 0.0), 0.0),
+        ];
+        for &(point, result) in expected.iter() {
+            assert!((result - light.intensity_at(point, &w)).abs() < 1e-5);
+        }
+    }
+    #[test]
+    fn a_directional_light_shines_from_the_same_direction_everywhere() {
+        let light = DirectionalLight::new(Vec3::new(0.0, -2.0, 0.0), Color::new(1.0, 1.0, 1.0));
+        for &point in [Point3::new(0.0, 0.0, 0.0), Point3::new(5.0, -3.0, 2.0)].iter() {
+            assert_eq!(Vec3::new(0.0, 1.0, 0.0), light.direction_to_light(point));
+            assert_eq!(f64::INFINITY, light.distance_to_light(point));
+            assert_eq!(Color::new(1.0, 1.0, 1.0), light.intensity(point));
+        }
+    }
+    #[test]
+    fn a_directional_light_is_shadowed_by_objects_in_its_way() {
+        let w = World::default();
+        let light = DirectionalLight::new(Vec3::new(0.0, -1.0, 0.0), Color::new(1.0, 1.0, 1.0));
+        assert_eq!(0.0, light.intensity_at(Point3::new(0.0, -2.0, 0.0), &w));
+        assert_eq!(1.0, light.intensity_at(Point3::new(2.0, -2.0, 0.0), &w));
+    }
+    #[test]
+    fn a_spot_light_fades_out_over_its_falloff_angle() {
+        let light = SpotLight::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+            PI / 8.0,
+            PI / 8.0,
+            Color::new(1.0, 1.0, 1.0),
+        );
+        // angle from the spot direction, expected intensity
+        let expected = [
+            (0.0, 1.0),
+            (PI / 16.0, 1.0),
+            (3.0 * PI / 16.0, 0.5),
+            (PI / 4.0, 0.0),
+            (PI / 2.0, 0.0),
+        ];
+        for &(angle, result) in expected.iter() {
+            let point = Point3::new(0.0, 10.0 * angle.sin(), 10.0 * angle.cos());
+            assert_eq!(Color::new(result, result, result), light.intensity(point));
+        }
+    }
+    #[test]
+    fn a_spot_light_points_towards_its_position() {
+        let light = SpotLight::new(
+            Point3::new(0.0, 10.0, 0.0),
+            Vec3::new(0.0, -1.0, 0.0),
+            PI / 6.0,
+            0.0,
+            Color::new(1.0, 1.0, 1.0),
+        );
+        let point = Point3::new(0.0, 0.0, 0.0);
+        assert_eq!(Vec3::new(0.0, 1.0, 0.0), light.direction_to_light(point));
+        assert_eq!(10.0, light.distance_to_light(point));
+        assert_eq!(Color::new(1.0, 1.0, 1.0), light.intensity(point));
+        assert_eq!(
+            Color::new(0.0, 0.0, 0.0),
+            light.intensity(Point3::new(10.0, 0.0, 0.0))
+        );
+    }
+    #[test]
+    fn a_point_light_is_an_area_light_of_one_cell() {
+        let light = PointLight::new(Point3::new(1.0, 2.0, 3.0), Color::new(1.0, 1.0, 1.0));
+        let area = AreaLight::from(light.clone());
+        assert_eq!(1, area.samples);
+        assert_eq!(light.position, area.position);
+        assert_eq!(vec![light.position], area.sample_points());
+    }
+    #[test]
+    fn a_sequence_cycles_through_its_values() {
+        let gen = Sequence::new(vec![0.1, 0.5, 1.0]);
+        assert!((0.1 - gen.next()).abs() < 1e-5);
+        assert!((0.5 - gen.next()).abs() < 1e-5);
+        assert!((1.0 - gen.next()).abs() < 1e-5);
+        assert!((0.1 - gen.next()).abs() < 1e-5);
+    }
+    #[test]
+    #[should_panic]
+    fn a_sequence_needs_at_least_one_value() {
+        Sequence::new(vec![]);
+    }
+    #[test]
+    fn a_random_sequence_stays_between_zero_and_one() {
+        let gen = Sequence::random(100);
+        for _ in 0..100 {
+            assert!((0.0..1.0).contains(&gen.next()));
+        }
+    }
+    #[test]
+    #[should_panic]
+    fn an_area_light_needs_at_least_one_cell_in_each_direction() {
+        AreaLight::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            4,
+            Vec3::new(0.0, 0.0, 1.0),
+            0,
+            Color::new(1.0, 1.0, 1.0),
+        );
+    }
+    #[test]
+    fn creating_an_area_light() {
+        let light = area_light();
+        assert_eq!(Point3::new(0.0, 0.0, 0.0), light.corner);
+        assert_eq!(Vec3::new(0.5, 0.0, 0.0), light.uvec);
+        assert_eq!(4, light.usteps);
+        assert_eq!(Vec3::new(0.0, 0.0, 0.5), light.vvec);
+        assert_eq!(2, light.vsteps);
+        assert_eq!(8, light.samples);
+        assert_eq!(Point3::new(1.0, 0.0, 0.5), light.position);
+    }
+    #[test]
+    fn finding_a_single_point_on_an_area_light() {
+        let mut light = area_light();
+        // Always the center of the cell
+        light.jitter_by = Sequence::new(vec![0.5]);
+        assert_eq!(Point3::new(0.25, 0.0, 0.25), light.point_on_light(0, 0));
+        assert_eq!(Point3::new(0.75, 0.0, 0.25), light.point_on_light(1, 0));
+        assert_eq!(Point3::new(0.25, 0.0, 0.75), light.point_on_light(0, 1));
+        assert_eq!(Point3::new(1.25, 0.0, 0.25), light.point_on_light(2, 0));
+        assert_eq!(Point3::new(1.75, 0.0, 0.75), light.point_on_light(3, 1));
+    }
+    #[test]
+    fn finding_a_single_point_on_a_jittered_area_light() {
+        let mut light = area_light();
+        light.jitter_by = Sequence::new(vec![0.3, 0.7]);
+        assert_eq!(Point3::new(0.15, 0.0, 0.35), light.point_on_light(0, 0));
+        assert_eq!(Point3::new(0.65, 0.0, 0.35), light.point_on_light(1, 0));
+        assert_eq!(Point3::new(0.15, 0.0, 0.85), light.point_on_light(0, 1));
+        assert_eq!(Point3::new(1.15, 0.0, 0.35), light.point_on_light(2, 0));
+        assert_eq!(Point3::new(1.65, 0.0, 0.85), light.point_on_light(3, 1));
+    }
+    #[test]
+    fn the_area_light_intensity_function() {
+        let w = World::default();
+        let mut light = AreaLight::new(
+            Point3::new(-0.5, -0.5, -5.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            2,
+            Vec3::new(0.0, 1.0, 0.0),
+            2,
+            Color::new(1.0, 1.0, 1.0),
+        );
+        light.jitter_by = Sequence::new(vec![0.5]);
+        let expected = [
+            (Point3::new(0.0, 0.0, 2.0), 0.0),
+            (Point3::new(1.0, -1.0, 2.0), 0.25),
+            (Point3::new(1.5, 0.0, 2.0), 0.5),
+            (Point3::new(1.25, 1.25, 3.0), 0.75),
+            (Point3::new(0.0, 0.0, -2.0), 1.0),
+        ];
+        for &(point, result) in expected.iter() {
+            assert!((result - light.intensity_at(point, &w)).abs() < 1e-5);
+        }
+    }
+    #[test]
+    fn the_area_light_with_jittered_samples() {
+        let w = World::default();
+        let expected = [
+            (Point3::new(0.0, 0.0, 2.0), 0.0),
+            (Point3::new(1.0, -1.0, 2.0), 0.5),
+            (Point3::new(1.5, 0.0, 2.0), 0.75),
+            (Point3::new(1.25, 1.25, 3.0), 0.75),
+            (Point3::new(0.0, 0.0, -2.0), 1.0),
+        ];
+        for &(point, result) in expected.iter() {
+            let mut light = AreaLight::new(
+                Point3::new(-0.5, -0.5, -5.0),
+                Vec3::new(1.0, 0.0, 0.0),
+                2,
+                Vec3::new(0.0, 1.0, 0.0),
+                2,
+                Color::new(1.0, 1.0, 1.0),
+            );
+            light.jitter_by = Sequence::new(vec![0.7, 0.3, 0.9, 0.1, 0.5]);
+            assert!((result - light.intensity_at(point, &w)).abs() < 1e-5);
+        }
+    }
 }
@@ -1,73 +1,136 @@
-use crate::lights::PointLight;
-use crate::tuple::Tuple;
+use crate::lights::Light;
+use crate::patterns::Pattern;
+use crate::shapes::Shape;
+use crate::tuple::{Color, Point3, Vec3};
 use std::sync::Arc;
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct Material {
-    pub color: Tuple,
+    pub color: Color,
+    // When set, the pattern is used instead of the flat color
+    pub pattern: Option<Arc<dyn Pattern>>,
     pub ambient: f64,
     pub diffuse: f64,
     pub specular: f64,
     pub shininess: f64,
+    // 0 is a matte surface, 1 is a perfect mirror
+    pub reflective: f64,
+    // 0 is opaque, 1 lets all light through
+    pub transparency: f64,
+    // How much light bends entering the material, 1 for vacuum, 1.5 for glass
+    pub refractive_index: f64,
 }
 
 impl Material {
     pub fn default() -> Material {
-        let c = Tuple::color(1.0, 1.0, 1.0);
+        let c = Color::new(1.0, 1.0, 1.0);
         Material {
             color: c,
+            pattern: None,
             ambient: 0.1,
             diffuse: 0.9,
             specular: 0.9,
             shininess: 200.0,
+            reflective: 0.0,
+            transparency: 0.0,
+            refractive_index: 1.0,
         }
     }
 
+    // intensity is the fraction of the light reaching the point, 0 when in shadow.
+    // The diffuse and specular parts are averaged over the samples of the light.
     pub fn lighting(
         &self,
-        light: &Arc<PointLight>,
-        point: Tuple,
-        eyev: Tuple,
-        normalv: Tuple,
-    ) -> Tuple {
-        let black = Tuple::color(0.0, 0.0, 0.0);
-        let diffuse;
-        let mut specular = Tuple::color(0.0, 0.0, 0.0);
-
+        object: &dyn Shape,
+        light: &dyn Light,
+        point: Point3,
+        eyev: Vec3,
+        normalv: Vec3,
+        intensity: f64,
+    ) -> Color {
         // Combine the surface color with the light's color/intensity
-        let effective_color: Tuple = self.color * light.intensity;
-
-        // Find the direction to the light source
-        let lightv = (light.position - point).normalize();
+        let light_intensity = light.intensity(point);
+        let effective_color = self.color_at(object, point) * light_intensity;
 
         // Compute the ambient contribution
         let ambient = effective_color * self.ambient;
 
+        let samples = light.samples(point);
+        let sum = samples
+            .iter()
+            .fold(Color::new(0.0, 0.0, 0.0), |sum, sample| {
+                sum + self.diffuse_specular(
+                    effective_color,
+                    light_intensity,
+                    sample.direction,
+                    eyev,
+                    normalv,
+                )
+            });
+        ambient + sum / samples.len() as f64 * intensity
+    }
+
+    fn color_at(&self, object: &dyn Shape, point: Point3) -> Color {
+        match &self.pattern {
+            Some(pattern) => pattern.pattern_at_shape(object, point),
+            None => self.color,
+        }
+    }
+
+    fn diffuse_specular(
+        &self,
+        effective_color: Color,
+        light_intensity: Color,
+        lightv: Vec3,
+        eyev: Vec3,
+        normalv: Vec3,
+    ) -> Color {
+        let black = Color::new(0.0, 0.0, 0.0);
+
         // Light_dot_normal represents the cosine of the angle between the
         // light vector and the normal vector. A negative number means
         // the light is on the other side of the surface.
         let light_dot_normal = lightv.dot(normalv);
         if light_dot_normal < 0.0 {
-            diffuse = black;
-        } else {
-            // Compute the diffuse contribution
-            diffuse = effective_color * self.diffuse * light_dot_normal;
+            return black;
+        }
+        // Compute the diffuse contribution
+        let diffuse = effective_color * self.diffuse * light_dot_normal;
 
-            // reflect_dot_eye represents the cosine of the angle betwen the
-            // reflection vector and the eye vector. A negative number means the
-            // light reflects away from the eye.
-            let reflectv = (-lightv).reflect(normalv);
-            let reflect_dot_eye = reflectv.dot(eyev);
+        // reflect_dot_eye represents the cosine of the angle betwen the
+        // reflection vector and the eye vector. A negative number means the
+        // light reflects away from the eye.
+        let reflectv = (-lightv).reflect(normalv);
+        let reflect_dot_eye = reflectv.dot(eyev);
 
-            if reflect_dot_eye <= 0.0 {
-                specular = black;
-            } else {
-                // Compute the specular contribution
-                let factor = reflect_dot_eye.powf(self.shininess);
-                specular = light.intensity * self.specular * factor;
-            }
-        }
-        ambient + diffuse + specular
+        let specular = if reflect_dot_eye <= 0.0 {
+            black
+        } else {
+            // Compute the specular contribution
+            let factor = reflect_dot_eye.powf(self.shininess);
+            light_intensity * self.specular * factor
+        };
+        diffuse + specular
+    }
+}
+
+// Patterns can't be compared, so two materials are only equal when sharing the same pattern
+impl PartialEq for Material {
+    fn eq(&self, other: &Material) -> bool {
+        let same_pattern = match (&self.pattern, &other.pattern) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        };
+        same_pattern
+            && self.color == other.color
+            && self.ambient == other.ambient
+            && self.diffuse == other.diffuse
+            && self.specular == other.specular
+            && self.shininess == other.shininess
+            && self.reflective == other.reflective
+            && self.transparency == other.transparency
+            && self.refractive_index == other.refractive_index
     }
 }
 
@@ -76,76 +139,172 @@ impl Material {
 
 mod tests {
     use super::*;
-    use crate::lights::PointLight;
+    use crate::lights::{AreaLight, PointLight, Sequence};
+    use crate::patterns::StripePattern;
+    use crate::spheres::Sphere;
+    use crate::world::World;
+    use std::f64::consts::FRAC_1_SQRT_2;
 
     #[test]
     fn the_default_material() {
         let m = Material::default();
-        let c = Tuple::color(1.0, 1.0, 1.0);
+        let c = Color::new(1.0, 1.0, 1.0);
         assert_eq!(c, m.color);
         assert_eq!(0.1, m.ambient);
         assert_eq!(0.9, m.diffuse);
         assert_eq!(0.9, m.specular);
         assert_eq!(200.0, m.shininess);
+        assert_eq!(0.0, m.reflective);
+        assert_eq!(0.0, m.transparency);
+        assert_eq!(1.0, m.refractive_index);
     }
     #[test]
     fn lightning_with_the_eye_between_the_light_and_the_surface() {
         let m = Material::default();
-        let position = Tuple::point(0.0, 0.0, 0.0);
-        let eyev = Tuple::vector(0.0, 0.0, -1.0);
-        let normalv = Tuple::vector(0.0, 0.0, -1.0);
-        let light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Tuple::color(1.0, 1.0, 1.0));
-        let alight = Arc::new(light);
-        let result = m.lighting(&alight, position, eyev, normalv);
-        let expected = Tuple::color(1.9, 1.9, 1.9);
+        let position = Point3::new(0.0, 0.0, 0.0);
+        let eyev = Vec3::new(0.0, 0.0, -1.0);
+        let normalv = Vec3::new(0.0, 0.0, -1.0);
+        let light = PointLight::new(Point3::new(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
+        let result = m.lighting(&Sphere::default(), &light, position, eyev, normalv, 1.0);
+        let expected = Color::new(1.9, 1.9, 1.9);
         assert_eq!(expected, result);
     }
     #[test]
     fn lightning_with_the_eye_between_the_light_and_the_surface_eye_offset_45_degrees() {
         let m = Material::default();
-        let position = Tuple::point(0.0, 0.0, 0.0);
-        let eyev = Tuple::vector(0.0, 2_f64.sqrt() / 2.0, -(2_f64.sqrt()) / 2.0);
-        let normalv = Tuple::vector(0.0, 0.0, -1.0);
-        let light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Tuple::color(1.0, 1.0, 1.0));
-        let alight = Arc::new(light);
-        let result = m.lighting(&alight, position, eyev, normalv);
-        let expected = Tuple::color(1.0, 1.0, 1.0);
+        let position = Point3::new(0.0, 0.0, 0.0);
+        let eyev = Vec3::new(0.0, 2_f64.sqrt() / 2.0, -(2_f64.sqrt()) / 2.0);
+        let normalv = Vec3::new(0.0, 0.0, -1.0);
+        let light = PointLight::new(Point3::new(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
+        let result = m.lighting(&Sphere::default(), &light, position, eyev, normalv, 1.0);
+        let expected = Color::new(1.0, 1.0, 1.0);
         assert_eq!(expected, result);
     }
     #[test]
     fn lightning_with_the_eye_opposite_surface_light_offset_45_degrees() {
         let m = Material::default();
-        let position = Tuple::point(0.0, 0.0, 0.0);
-        let eyev = Tuple::vector(0.0, 0.0, -1.0);
-        let normalv = Tuple::vector(0.0, 0.0, -1.0);
-        let light = PointLight::new(Tuple::point(0.0, 10.0, -10.0), Tuple::color(1.0, 1.0, 1.0));
-        let alight = Arc::new(light);
-        let result = m.lighting(&alight, position, eyev, normalv);
-        let expected = Tuple::color(0.7364, 0.7364, 0.7364);
+        let position = Point3::new(0.0, 0.0, 0.0);
+        let eyev = Vec3::new(0.0, 0.0, -1.0);
+        let normalv = Vec3::new(0.0, 0.0, -1.0);
+        let light = PointLight::new(Point3::new(0.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0));
+        let result = m.lighting(&Sphere::default(), &light, position, eyev, normalv, 1.0);
+        let expected = Color::new(0.7364, 0.7364, 0.7364);
         assert_eq!(expected, result);
     }
     #[test]
     fn lightning_with_the_eye_in_the_path_of_the_reflection_vector() {
         let m = Material::default();
-        let position = Tuple::point(0.0, 0.0, 0.0);
-        let eyev = Tuple::vector(0.0, -(2_f64.sqrt()) / 2.0, -(2_f64.sqrt()) / 2.0);
-        let normalv = Tuple::vector(0.0, 0.0, -1.0);
-        let light = PointLight::new(Tuple::point(0.0, 10.0, -10.0), Tuple::color(1.0, 1.0, 1.0));
-        let alight = Arc::new(light);
-        let result = m.lighting(&alight, position, eyev, normalv);
-        let expected = Tuple::color(1.6364, 1.6364, 1.6364);
+        let position = Point3::new(0.0, 0.0, 0.0);
+        let eyev = Vec3::new(0.0, -(2_f64.sqrt()) / 2.0, -(2_f64.sqrt()) / 2.0);
+        let normalv = Vec3::new(0.0, 0.0, -1.0);
+        let light = PointLight::new(Point3::new(0.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0));
+        let result = m.lighting(&Sphere::default(), &light, position, eyev, normalv, 1.0);
+        let expected = Color::new(1.6364, 1.6364, 1.6364);
         assert_eq!(expected, result);
     }
     #[test]
     fn lightning_with_the_light_behind_the_surface() {
         let m = Material::default();
-        let position = Tuple::point(0.0, 0.0, 0.0);
-        let eyev = Tuple::vector(0.0, 0.0, -1.0);
-        let normalv = Tuple::vector(0.0, 0.0, -1.0);
-        let light = PointLight::new(Tuple::point(0.0, 0.0, 10.0), Tuple::color(1.0, 1.0, 1.0));
-        let alight = Arc::new(light);
-        let result = m.lighting(&alight, position, eyev, normalv);
-        let expected = Tuple::color(0.1, 0.1, 0.1);
+        let position = Point3::new(0.0, 0.0, 0.0);
+        let eyev = Vec3::new(0.0, 0.0, -1.0);
+        let normalv = Vec3::new(0.0, 0.0, -1.0);
+        let light = PointLight::new(Point3::new(0.0, 0.0, 10.0), Color::new(1.0, 1.0, 1.0));
+        let result = m.lighting(&Sphere::default(), &light, position, eyev, normalv, 1.0);
+        let expected = Color::new(0.1, 0.1, 0.1);
         assert_eq!(expected, result);
     }
+    #[test]
+    fn lightning_with_the_surface_in_shadow() {
+        let m = Material::default();
+        let position = Point3::new(0.0, 0.0, 0.0);
+        let eyev = Vec3::new(0.0, 0.0, -1.0);
+        let normalv = Vec3::new(0.0, 0.0, -1.0);
+        let light = PointLight::new(Point3::new(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
+        // No light reaches a point in shadow
+        let result = m.lighting(&Sphere::default(), &light, position, eyev, normalv, 0.0);
+        let expected = Color::new(0.1, 0.1, 0.1);
+        assert_eq!(expected, result);
+    }
+    #[test]
+    fn lighting_with_a_pattern_applied() {
+        let mut m = Material::default();
+        m.pattern = Some(Arc::new(StripePattern::new(
+            Color::new(1.0, 1.0, 1.0),
+            Color::new(0.0, 0.0, 0.0),
+        )));
+        m.ambient = 1.0;
+        m.diffuse = 0.0;
+        m.specular = 0.0;
+        let eyev = Vec3::new(0.0, 0.0, -1.0);
+        let normalv = Vec3::new(0.0, 0.0, -1.0);
+        let light = PointLight::new(Point3::new(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
+        let object = Sphere::default();
+        let c1 = m.lighting(
+            &object,
+            &light,
+            Point3::new(0.9, 0.0, 0.0),
+            eyev,
+            normalv,
+            1.0,
+        );
+        let c2 = m.lighting(
+            &object,
+            &light,
+            Point3::new(1.1, 0.0, 0.0),
+            eyev,
+            normalv,
+            1.0,
+        );
+        assert_eq!(Color::new(1.0, 1.0, 1.0), c1);
+        assert_eq!(Color::new(0.0, 0.0, 0.0), c2);
+    }
+    #[test]
+    fn lighting_uses_light_intensity_to_attenuate_color() {
+        let w = World::default();
+        let light = PointLight::new(Point3::new(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
+        let shape = w.objects[0].as_ref();
+        let mut m = shape.material().clone();
+        m.ambient = 0.1;
+        m.diffuse = 0.9;
+        m.specular = 0.0;
+        m.color = Color::new(1.0, 1.0, 1.0);
+        let point = Point3::new(0.0, 0.0, -1.0);
+        let eyev = Vec3::new(0.0, 0.0, -1.0);
+        let normalv = Vec3::new(0.0, 0.0, -1.0);
+        let expected = [(1.0, 1.0), (0.5, 0.55), (0.0, 0.1)];
+        for &(intensity, result) in expected.iter() {
+            let c = m.lighting(shape, &light, point, eyev, normalv, intensity);
+            assert_eq!(Color::new(result, result, result), c);
+        }
+    }
+    #[test]
+    fn lighting_samples_the_area_light() {
+        let mut light = AreaLight::new(
+            Point3::new(-0.5, -0.5, -5.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            2,
+            Vec3::new(0.0, 1.0, 0.0),
+            2,
+            Color::new(1.0, 1.0, 1.0),
+        );
+        light.jitter_by = Sequence::new(vec![0.5]);
+        let shape = Sphere::default();
+        let mut m = Material::default();
+        m.ambient = 0.1;
+        m.diffuse = 0.9;
+        m.specular = 0.0;
+        let eye = Point3::new(0.0, 0.0, -5.0);
+        let expected = [
+            (Point3::new(0.0, 0.0, -1.0), 0.9965),
+            (Point3::new(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2), 0.62318),
+        ];
+        for &(point, result) in expected.iter() {
+            let eyev = (eye - point).normalize();
+            let normalv = point - Point3::origin();
+            let c = m.lighting(&shape, &light, point, eyev, normalv, 1.0);
+            assert!((result - c.0).abs() < 1e-4);
+            assert_eq!(c.0, c.1);
+            assert_eq!(c.0, c.2);
+        }
+    }
 }
@@ -1,6 +1,31 @@
-use crate::tuple::Tuple;
+use crate::simd;
+use crate::tuple::{Point3, Tuple, Vec3};
+use std::fmt;
 use std::ops::Mul;
 
+// A determinant this small relative to the rows of the matrix is treated as zero
+const SINGULAR_EPSILON: f64 = 1e-12;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SingularMatrix;
+
+impl fmt::Display for SingularMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Matrix is singular and has no inverse")
+    }
+}
+
+impl std::error::Error for SingularMatrix {}
+
+// The determinant is bounded by the product of the row lengths (Hadamard's
+// inequality), comparing against that makes the test independent of scale
+fn is_singular<'a>(determinant: f64, rows: impl Iterator<Item = &'a [f64]>) -> bool {
+    let bound: f64 = rows
+        .map(|row| row.iter().map(|e| e * e).sum::<f64>().sqrt())
+        .product();
+    determinant.abs() <= SINGULAR_EPSILON * bound
+}
+
 #[derive(Debug, Clone)]
 pub struct Matrix {
     pub dimensions: usize,
@@ -81,20 +106,22 @@ impl Matrix {
     }
 
     pub fn invertible(&self) -> bool {
-        self.determinant() != 0.0
+        !is_singular(self.determinant(), self.elements.chunks(self.dimensions))
     }
 
-    pub fn inverse(&self) -> Matrix {
-        assert!(self.invertible());
-        let mut new_vec: Vec<f64> = Vec::with_capacity(self.dimensions * self.dimensions);
+    pub fn try_inverse(&self) -> Result<Matrix, SingularMatrix> {
         let determinant = self.determinant();
+        if is_singular(determinant, self.elements.chunks(self.dimensions)) {
+            return Err(SingularMatrix);
+        }
+        let mut new_vec: Vec<f64> = Vec::with_capacity(self.dimensions * self.dimensions);
         for row in 0..self.dimensions {
             for col in 0..self.dimensions {
                 let c = self.cofactor(col, row);
                 new_vec.push(c / determinant)
             }
         }
-        Matrix::from_vector(self.dimensions, &new_vec)
+        Ok(Matrix::from_vector(self.dimensions, &new_vec))
     }
 }
 impl PartialEq for Matrix {
@@ -153,6 +180,211 @@ impl Mul<Tuple> for Matrix {
         Tuple(new_vec[0], new_vec[1], new_vec[2], new_vec[3])
     }
 }
+// A 4x4 matrix on the stack, used for all transforms. Being Copy it can be
+// passed around freely, and the inverse is computed without allocating.
+#[derive(Debug, Clone, Copy)]
+pub struct Matrix4 {
+    elements: [[f64; 4]; 4],
+}
+
+impl Matrix4 {
+    pub fn new(elements: [[f64; 4]; 4]) -> Matrix4 {
+        Matrix4 { elements }
+    }
+
+    pub fn from_vector(e: &[f64]) -> Matrix4 {
+        assert!(e.len() == 16);
+        let mut elements = [[0.0; 4]; 4];
+        for (r, row) in elements.iter_mut().enumerate() {
+            row.copy_from_slice(&e[r * 4..r * 4 + 4]);
+        }
+        Matrix4 { elements }
+    }
+
+    pub fn at(&self, r: usize, c: usize) -> f64 {
+        self.elements[r][c]
+    }
+    pub fn update_at(mut self, r: usize, c: usize, v: f64) -> Matrix4 {
+        self.elements[r][c] = v;
+        self
+    }
+
+    pub fn identity() -> Matrix4 {
+        Matrix4::new([
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn transpose(&self) -> Matrix4 {
+        let mut elements = [[0.0; 4]; 4];
+        for (r, row) in elements.iter_mut().enumerate() {
+            for (c, e) in row.iter_mut().enumerate() {
+                *e = self.elements[c][r];
+            }
+        }
+        Matrix4 { elements }
+    }
+
+    // The 2x2 determinants of the top two and bottom two rows, shared by the
+    // determinant and the inverse (Laplace expansion along the row pairs)
+    fn sub_determinants(&self) -> ([f64; 6], [f64; 6]) {
+        let m = &self.elements;
+        let s = [
+            m[0][0] * m[1][1] - m[1][0] * m[0][1],
+            m[0][0] * m[1][2] - m[1][0] * m[0][2],
+            m[0][0] * m[1][3] - m[1][0] * m[0][3],
+            m[0][1] * m[1][2] - m[1][1] * m[0][2],
+            m[0][1] * m[1][3] - m[1][1] * m[0][3],
+            m[0][2] * m[1][3] - m[1][2] * m[0][3],
+        ];
+        let c = [
+            m[2][0] * m[3][1] - m[3][0] * m[2][1],
+            m[2][0] * m[3][2] - m[3][0] * m[2][2],
+            m[2][0] * m[3][3] - m[3][0] * m[2][3],
+            m[2][1] * m[3][2] - m[3][1] * m[2][2],
+            m[2][1] * m[3][3] - m[3][1] * m[2][3],
+            m[2][2] * m[3][3] - m[3][2] * m[2][3],
+        ];
+        (s, c)
+    }
+
+    pub fn determinant(&self) -> f64 {
+        let (s, c) = self.sub_determinants();
+        expand_determinant(&s, &c)
+    }
+
+    pub fn invertible(&self) -> bool {
+        !is_singular(self.determinant(), self.rows())
+    }
+
+    pub fn try_inverse(&self) -> Result<Matrix4, SingularMatrix> {
+        let (s, c) = self.sub_determinants();
+        let det = expand_determinant(&s, &c);
+        if is_singular(det, self.rows()) {
+            return Err(SingularMatrix);
+        }
+        let m = &self.elements;
+        let inv = 1.0 / det;
+        Ok(Matrix4::new([
+            [
+                (m[1][1] * c[5] - m[1][2] * c[4] + m[1][3] * c[3]) * inv,
+                (-m[0][1] * c[5] + m[0][2] * c[4] - m[0][3] * c[3]) * inv,
+                (m[3][1] * s[5] - m[3][2] * s[4] + m[3][3] * s[3]) * inv,
+                (-m[2][1] * s[5] + m[2][2] * s[4] - m[2][3] * s[3]) * inv,
+            ],
+            [
+                (-m[1][0] * c[5] + m[1][2] * c[2] - m[1][3] * c[1]) * inv,
+                (m[0][0] * c[5] - m[0][2] * c[2] + m[0][3] * c[1]) * inv,
+                (-m[3][0] * s[5] + m[3][2] * s[2] - m[3][3] * s[1]) * inv,
+                (m[2][0] * s[5] - m[2][2] * s[2] + m[2][3] * s[1]) * inv,
+            ],
+            [
+                (m[1][0] * c[4] - m[1][1] * c[2] + m[1][3] * c[0]) * inv,
+                (-m[0][0] * c[4] + m[0][1] * c[2] - m[0][3] * c[0]) * inv,
+                (m[3][0] * s[4] - m[3][1] * s[2] + m[3][3] * s[0]) * inv,
+                (-m[2][0] * s[4] + m[2][1] * s[2] - m[2][3] * s[0]) * inv,
+            ],
+            [
+                (-m[1][0] * c[3] + m[1][1] * c[1] - m[1][2] * c[0]) * inv,
+                (m[0][0] * c[3] - m[0][1] * c[1] + m[0][2] * c[0]) * inv,
+                (-m[3][0] * s[3] + m[3][1] * s[1] - m[3][2] * s[0]) * inv,
+                (m[2][0] * s[3] - m[2][1] * s[1] + m[2][2] * s[0]) * inv,
+            ],
+        ]))
+    }
+
+    fn rows(&self) -> impl Iterator<Item = &[f64]> {
+        self.elements.iter().map(|row| &row[..])
+    }
+}
+
+fn expand_determinant(s: &[f64; 6], c: &[f64; 6]) -> f64 {
+    s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0]
+}
+
+impl PartialEq for Matrix4 {
+    fn eq(&self, other: &Matrix4) -> bool {
+        let eps = 1e-5;
+        self.elements
+            .iter()
+            .flatten()
+            .zip(other.elements.iter().flatten())
+            .all(|(x, y)| (x - y).abs() < eps)
+    }
+}
+
+impl Mul<&Matrix4> for &Matrix4 {
+    type Output = Matrix4;
+    fn mul(self, other: &Matrix4) -> Matrix4 {
+        let mut elements = [[0.0; 4]; 4];
+        for (r, row) in elements.iter_mut().enumerate() {
+            for (c, e) in row.iter_mut().enumerate() {
+                *e = self.elements[r][0] * other.elements[0][c]
+                    + self.elements[r][1] * other.elements[1][c]
+                    + self.elements[r][2] * other.elements[2][c]
+                    + self.elements[r][3] * other.elements[3][c];
+            }
+        }
+        Matrix4 { elements }
+    }
+}
+
+impl Mul<Matrix4> for Matrix4 {
+    type Output = Matrix4;
+    fn mul(self, other: Matrix4) -> Matrix4 {
+        &self * &other
+    }
+}
+
+impl Mul<Tuple> for &Matrix4 {
+    type Output = Tuple;
+    fn mul(self, other: Tuple) -> Tuple {
+        Tuple::from_lanes(simd::mul_matrix(&self.elements, other.lanes()))
+    }
+}
+
+impl Mul<Tuple> for Matrix4 {
+    type Output = Tuple;
+    fn mul(self, other: Tuple) -> Tuple {
+        &self * other
+    }
+}
+
+// Transforms are affine, so w stays 1 and is dropped. The shared kernel
+// still multiplies all four rows, the bottom one is just ignored.
+impl Mul<Point3> for &Matrix4 {
+    type Output = Point3;
+    fn mul(self, p: Point3) -> Point3 {
+        let r = simd::mul_matrix(&self.elements, p.lanes());
+        Point3(r[0], r[1], r[2])
+    }
+}
+
+impl Mul<Point3> for Matrix4 {
+    type Output = Point3;
+    fn mul(self, p: Point3) -> Point3 {
+        &self * p
+    }
+}
+
+// With w = 0 the translation column has no effect on a vector
+impl Mul<Vec3> for &Matrix4 {
+    type Output = Vec3;
+    fn mul(self, v: Vec3) -> Vec3 {
+        Vec3::from_lanes(simd::mul_matrix(&self.elements, v.lanes()))
+    }
+}
+
+impl Mul<Vec3> for Matrix4 {
+    type Output = Vec3;
+    fn mul(self, v: Vec3) -> Vec3 {
+        &self * v
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::float_cmp)]
 
@@ -371,7 +603,7 @@ mod tests {
             0.21805, 0.45113, 0.24060, -0.04511, -0.80827, -1.45677, -0.44361, 0.52068, -0.07895,
             -0.22368, -0.05263, 0.19737, -0.52256, -0.81391, -0.30075, 0.30639,
         ];
-        let b = a.inverse();
+        let b = a.try_inverse().unwrap();
         let expected = Matrix::from_vector(4, &v2);
 
         assert_eq!(532.0, a.determinant());
@@ -394,7 +626,7 @@ mod tests {
             0.35897, 0.43590, 0.92308, -0.69231, -0.69231, -0.76923, -1.92308,
         ];
 
-        assert_eq!(Matrix::from_vector(4, &v2), a.inverse());
+        assert_eq!(Matrix::from_vector(4, &v2), a.try_inverse().unwrap());
     }
 
     #[test]
@@ -409,7 +641,7 @@ mod tests {
             -0.14630, -0.10926, 0.12963, 0.17778, 0.06667, -0.26667, 0.33333,
         ];
 
-        assert_eq!(Matrix::from_vector(4, &v2), a.inverse());
+        assert_eq!(Matrix::from_vector(4, &v2), a.try_inverse().unwrap());
     }
 
     #[test]
@@ -427,7 +659,160 @@ mod tests {
         let b2 = Matrix::from_vector(4, &v2);
         let c = a * b;
 
-        assert_eq!(c * b2.inverse(), a2);
+        assert_eq!(c * b2.try_inverse().unwrap(), a2);
+    }
+    #[test]
+    fn constructing_and_inspecting_a_matrix4() {
+        let m = Matrix4::new([
+            [1.0, 2.0, 3.0, 4.0],
+            [5.5, 6.5, 7.5, 8.5],
+            [9.0, 10.0, 11.0, 12.0],
+            [13.5, 14.5, 15.5, 16.5],
+        ]);
+        assert_eq!(m.at(0, 3), 4.0);
+        assert_eq!(m.at(1, 2), 7.5);
+        assert_eq!(m.at(3, 0), 13.5);
+        assert_eq!(m, m.update_at(2, 2, 11.0));
+        assert_eq!(-1.0, m.update_at(2, 2, -1.0).at(2, 2));
+    }
+    #[test]
+    fn multiplying_two_matrix4s() {
+        let a = Matrix4::from_vector(&[
+            1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 8.0, 7.0, 6.0, 5.0, 4.0, 3.0, 2.0,
+        ]);
+        let b = Matrix4::from_vector(&[
+            -2.0, 1.0, 2.0, 3.0, 3.0, 2.0, 1.0, -1.0, 4.0, 3.0, 6.0, 5.0, 1.0, 2.0, 7.0, 8.0,
+        ]);
+        let expected = Matrix4::from_vector(&[
+            20.0, 22.0, 50.0, 48.0, 44.0, 54.0, 114.0, 108.0, 40.0, 58.0, 110.0, 102.0, 16.0, 26.0,
+            46.0, 42.0,
+        ]);
+        assert_eq!(expected, a * b);
+        assert_eq!(a, a * Matrix4::identity());
+    }
+    #[test]
+    fn multiplying_matrix4_by_tuple() {
+        let a = Matrix4::from_vector(&[
+            1.0, 2.0, 3.0, 4.0, 2.0, 4.0, 4.0, 2.0, 8.0, 6.0, 4.0, 1.0, 0.0, 0.0, 0.0, 1.0,
+        ]);
+        let b = Tuple(1.0, 2.0, 3.0, 1.0);
+        assert_eq!(Tuple(18.0, 24.0, 33.0, 1.0), a * b);
+    }
+    #[test]
+    fn transposing_a_matrix4() {
+        let a = Matrix4::from_vector(&[
+            0.0, 9.0, 3.0, 0.0, 9.0, 8.0, 0.0, 8.0, 1.0, 8.0, 5.0, 3.0, 0.0, 0.0, 5.0, 8.0,
+        ]);
+        let expected = Matrix4::from_vector(&[
+            0.0, 9.0, 1.0, 0.0, 9.0, 8.0, 8.0, 0.0, 3.0, 0.0, 5.0, 5.0, 0.0, 8.0, 3.0, 8.0,
+        ]);
+        assert_eq!(expected, a.transpose());
+        assert_eq!(Matrix4::identity(), Matrix4::identity().transpose());
+    }
+    #[test]
+    fn matrix4_determinant_and_invertibility() {
+        let a = Matrix4::from_vector(&[
+            -2.0, -8.0, 3.0, 5.0, -3.0, 1.0, 7.0, 3.0, 1.0, 2.0, -9.0, 6.0, -6.0, 7.0, 7.0, -9.0,
+        ]);
+        assert_eq!(-4071.0, a.determinant());
+        assert!(a.invertible());
+        let b = Matrix4::from_vector(&[
+            -4.0, 2.0, -2.0, -3.0, 9.0, 6.0, 2.0, 6.0, 0.0, -5.0, 1.0, -5.0, 0.0, 0.0, 0.0, 0.0,
+        ]);
+        assert_eq!(0.0, b.determinant());
+        assert!(!b.invertible());
+    }
+    #[test]
+    fn matrix4_inverse_matches_the_cofactor_inverse() {
+        let vs = [
+            [
+                -5.0, 2.0, 6.0, -8.0, 1.0, -5.0, 1.0, 8.0, 7.0, 7.0, -6.0, -7.0, 1.0, -3.0, 7.0,
+                4.0,
+            ],
+            [
+                8.0, -5.0, 9.0, 2.0, 7.0, 5.0, 6.0, 1.0, -6.0, 0.0, 9.0, 6.0, -3.0, 0.0, -9.0, -4.0,
+            ],
+            [
+                9.0, 3.0, 0.0, 9.0, -5.0, -2.0, -6.0, -3.0, -4.0, 9.0, 6.0, 4.0, -7.0, 6.0, 6.0,
+                2.0,
+            ],
+        ];
+        for v in vs.iter() {
+            let a = Matrix4::from_vector(v);
+            let b = Matrix::from_vector(4, v);
+            assert_eq!(b.determinant(), a.determinant());
+            let inverse = a.try_inverse().unwrap();
+            let expected = b.try_inverse().unwrap();
+            for r in 0..4 {
+                for c in 0..4 {
+                    assert!((expected.at(r, c) - inverse.at(r, c)).abs() < 1e-10);
+                }
+            }
+        }
+    }
+    #[test]
+    fn multiplying_matrix4_product_by_its_inverse() {
+        let a = Matrix4::from_vector(&[
+            3.0, -9.0, 7.0, 3.0, 3.0, -8.0, 2.0, -9.0, -4.0, 4.0, 4.0, 1.0, -6.0, 5.0, -1.0, 1.0,
+        ]);
+        let b = Matrix4::from_vector(&[
+            8.0, 2.0, 2.0, 2.0, 3.0, -1.0, 7.0, 0.0, 7.0, 0.0, 5.0, 4.0, 6.0, -2.0, 0.0, 5.0,
+        ]);
+        let c = a * b;
+        assert_eq!(a, c * b.try_inverse().unwrap());
+    }
+    #[test]
+    fn singular_matrices_have_no_inverse() {
+        let v = [
+            -4.0, 2.0, -2.0, -3.0, 9.0, 6.0, 2.0, 6.0, 0.0, -5.0, 1.0, -5.0, 0.0, 0.0, 0.0, 0.0,
+        ];
+        assert_eq!(
+            Err(SingularMatrix),
+            Matrix::from_vector(4, &v).try_inverse()
+        );
+        assert_eq!(Err(SingularMatrix), Matrix4::from_vector(&v).try_inverse());
+    }
+    #[test]
+    fn the_singularity_test_is_relative_to_the_size_of_the_elements() {
+        // Tiny but well conditioned, the determinant is 1e-24
+        let small = Matrix4::identity()
+            .update_at(0, 0, 1e-6)
+            .update_at(1, 1, 1e-6)
+            .update_at(2, 2, 1e-6)
+            .update_at(3, 3, 1e-6);
+        assert!(small.invertible());
+        assert!(small.try_inverse().is_ok());
+        // Two rows that are equal apart from rounding noise
+        let almost = Matrix4::from_vector(&[
+            1.0,
+            2.0,
+            3.0,
+            4.0,
+            1.0,
+            2.0,
+            3.0,
+            4.0 + 1e-15,
+            0.0,
+            1.0,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            1.0,
+        ]);
+        assert!(!almost.invertible());
+        assert_eq!(Err(SingularMatrix), almost.try_inverse());
+    }
+    #[test]
+    fn multiplying_matrix4_by_point_and_vector() {
+        let a = Matrix4::from_vector(&[
+            1.0, 2.0, 3.0, 4.0, 2.0, 4.0, 4.0, 2.0, 8.0, 6.0, 4.0, 1.0, 0.0, 0.0, 0.0, 1.0,
+        ]);
+        assert_eq!(
+            Point3::new(18.0, 24.0, 33.0),
+            a * Point3::new(1.0, 2.0, 3.0)
+        );
+        assert_eq!(Vec3::new(14.0, 22.0, 32.0), a * Vec3::new(1.0, 2.0, 3.0));
     }
-
 }
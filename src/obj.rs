@@ -0,0 +1,434 @@
+use crate::groups::Group;
+use crate::shapes::Shape;
+use crate::triangles::{SmoothTriangle, Triangle};
+use crate::tuple::{Point3, Vec3};
+use std::fmt;
+use std::fs;
+use std::io;
+
+#[derive(Debug)]
+pub enum ObjError {
+    Io(io::Error),
+    // A line with a known statement that could not be parsed
+    Malformed { line: usize, content: String },
+    // A face referring to a vertex or normal that does not exist
+    InvalidIndex { line: usize, index: i64 },
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ObjError::Io(e) => write!(f, "Reading obj file failed: {}", e),
+            ObjError::Malformed { line, content } => {
+                write!(f, "Malformed obj statement on line {}: {}", line, content)
+            }
+            ObjError::InvalidIndex { line, index } => {
+                write!(f, "Invalid index {} on line {}", index, line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ObjError {}
+
+impl From<io::Error> for ObjError {
+    fn from(e: io::Error) -> ObjError {
+        ObjError::Io(e)
+    }
+}
+
+// A triangle as zero based indices into the vertices and normals of the file
+#[derive(Debug, PartialEq, Clone)]
+pub struct Face {
+    pub vertices: [usize; 3],
+    pub normals: Option<[usize; 3]>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ObjGroup {
+    pub name: String,
+    pub faces: Vec<Face>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ObjFile {
+    pub vertices: Vec<Point3>,
+    pub normals: Vec<Vec3>,
+    // The first group is the unnamed default group for faces before any "g" statement
+    pub groups: Vec<ObjGroup>,
+    // Line numbers (starting at 1) of statements that are not supported
+    pub ignored: Vec<usize>,
+}
+
+impl ObjFile {
+    pub fn default_group(&self) -> &ObjGroup {
+        &self.groups[0]
+    }
+
+    pub fn group(&self, name: &str) -> Option<&ObjGroup> {
+        self.groups.iter().find(|g| g.name == name)
+    }
+
+    pub fn face_to_shape(&self, face: &Face) -> Box<dyn Shape> {
+        let [p1, p2, p3] = face.vertices;
+        match face.normals {
+            Some([n1, n2, n3]) => Box::new(SmoothTriangle::new(
+                self.vertices[p1],
+                self.vertices[p2],
+                self.vertices[p3],
+                self.normals[n1],
+                self.normals[n2],
+                self.normals[n3],
+            )),
+            None => Box::new(Triangle::new(
+                self.vertices[p1],
+                self.vertices[p2],
+                self.vertices[p3],
+            )),
+        }
+    }
+
+    // The default group's triangles are added directly, each named group becomes a child group
+    pub fn to_group(&self) -> Group {
+        let mut group = Group::default();
+        for obj_group in self.groups.iter() {
+            if obj_group.name.is_empty() {
+                for face in obj_group.faces.iter() {
+                    group.add_child(self.face_to_shape(face));
+                }
+            } else {
+                let mut child = Group::default();
+                for face in obj_group.faces.iter() {
+                    child.add_child(self.face_to_shape(face));
+                }
+                group.add_child(Box::new(child));
+            }
+        }
+        group
+    }
+}
+
+pub fn read_obj_file(filename: &str) -> Result<ObjFile, ObjError> {
+    let content = fs::read_to_string(filename)?;
+    parse_obj_file(&content)
+}
+
+pub fn parse_obj_file(content: &str) -> Result<ObjFile, ObjError> {
+    let mut obj = ObjFile {
+        vertices: Vec::new(),
+        normals: Vec::new(),
+        groups: vec![ObjGroup {
+            name: String::new(),
+            faces: Vec::new(),
+        }],
+        ignored: Vec::new(),
+    };
+
+    for (i, raw_line) in content.lines().enumerate() {
+        let line = i + 1;
+        let malformed = || ObjError::Malformed {
+            line,
+            content: raw_line.to_string(),
+        };
+        let mut parts = raw_line.split_whitespace();
+        let statement = match parts.next() {
+            Some(s) => s,
+            None => continue,
+        };
+        let args: Vec<&str> = parts.collect();
+        match statement {
+            "v" => {
+                let (x, y, z) = parse_xyz(&args).ok_or_else(malformed)?;
+                obj.vertices.push(Point3::new(x, y, z));
+            }
+            "vn" => {
+                let (x, y, z) = parse_xyz(&args).ok_or_else(malformed)?;
+                obj.normals.push(Vec3::new(x, y, z));
+            }
+            "f" => {
+                if args.len() < 3 {
+                    return Err(malformed());
+                }
+                let mut vertices = Vec::new();
+                let mut normals = Vec::new();
+                for arg in args {
+                    let (v, n) = split_face_vertex(arg).ok_or_else(malformed)?;
+                    vertices.push(resolve_index(v, obj.vertices.len(), line)?);
+                    normals.push(match n {
+                        Some(n) => Some(resolve_index(n, obj.normals.len(), line)?),
+                        None => None,
+                    });
+                }
+                // Polygons are split into a fan of triangles around the first vertex
+                let group = obj.groups.last_mut().unwrap();
+                for index in 1..vertices.len() - 1 {
+                    let face_normals = match (normals[0], normals[index], normals[index + 1]) {
+                        (Some(n1), Some(n2), Some(n3)) => Some([n1, n2, n3]),
+                        _ => None,
+                    };
+                    group.faces.push(Face {
+                        vertices: [vertices[0], vertices[index], vertices[index + 1]],
+                        normals: face_normals,
+                    });
+                }
+            }
+            "g" => {
+                if args.is_empty() {
+                    return Err(malformed());
+                }
+                obj.groups.push(ObjGroup {
+                    name: args.join(" "),
+                    faces: Vec::new(),
+                });
+            }
+            s if s.starts_with('#') => continue,
+            _ => obj.ignored.push(line),
+        }
+    }
+    Ok(obj)
+}
+
+fn parse_xyz(args: &[&str]) -> Option<(f64, f64, f64)> {
+    if args.len() < 3 {
+        return None;
+    }
+    let x = args[0].parse().ok()?;
+    let y = args[1].parse().ok()?;
+    let z = args[2].parse().ok()?;
+    Some((x, y, z))
+}
+
+// Splits "v", "v/vt", "v//vn" or "v/vt/vn" into the vertex and normal index,
+// texture coordinates are not used
+fn split_face_vertex(arg: &str) -> Option<(i64, Option<i64>)> {
+    let mut parts = arg.split('/');
+    let vertex = parts.next()?.parse().ok()?;
+    let _texture = parts.next();
+    let normal = match parts.next() {
+        Some(n) => Some(n.parse().ok()?),
+        None => None,
+    };
+    Some((vertex, normal))
+}
+
+// Obj indices start at 1, negative indices count backwards from the last element
+fn resolve_index(index: i64, len: usize, line: usize) -> Result<usize, ObjError> {
+    let resolved = if index < 0 {
+        len as i64 + index
+    } else {
+        index - 1
+    };
+    if resolved < 0 || resolved >= len as i64 {
+        Err(ObjError::InvalidIndex { line, index })
+    } else {
+        Ok(resolved as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rays::Ray;
+    use crate::transformations::translation;
+
+    #[test]
+    fn ignoring_unrecognized_lines() {
+        let gibberish = "There was a young lady named Bright
+who traveled much faster than light.
+She set out one day
+in a relative way,
+and came back the previous night.";
+        let obj = parse_obj_file(gibberish).unwrap();
+        assert_eq!(vec![1, 2, 3, 4, 5], obj.ignored);
+    }
+    #[test]
+    fn vertex_records() {
+        let file = "v -1 1 0
+v -1.0000 0.5000 0.0000
+v 1 0 0
+v 1 1 0";
+        let obj = parse_obj_file(file).unwrap();
+        assert_eq!(Point3::new(-1.0, 1.0, 0.0), obj.vertices[0]);
+        assert_eq!(Point3::new(-1.0, 0.5, 0.0), obj.vertices[1]);
+        assert_eq!(Point3::new(1.0, 0.0, 0.0), obj.vertices[2]);
+        assert_eq!(Point3::new(1.0, 1.0, 0.0), obj.vertices[3]);
+    }
+    #[test]
+    fn parsing_triangle_faces() {
+        let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+
+f 1 2 3
+f 1 3 4";
+        let obj = parse_obj_file(file).unwrap();
+        let g = obj.default_group();
+        assert_eq!([0, 1, 2], g.faces[0].vertices);
+        assert_eq!([0, 2, 3], g.faces[1].vertices);
+        assert!(obj.ignored.is_empty());
+    }
+    #[test]
+    fn triangulating_polygons() {
+        let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+v 0 2 0
+
+f 1 2 3 4 5";
+        let obj = parse_obj_file(file).unwrap();
+        let g = obj.default_group();
+        assert_eq!(3, g.faces.len());
+        assert_eq!([0, 1, 2], g.faces[0].vertices);
+        assert_eq!([0, 2, 3], g.faces[1].vertices);
+        assert_eq!([0, 3, 4], g.faces[2].vertices);
+    }
+    #[test]
+    fn triangles_in_groups() {
+        let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+
+g FirstGroup
+f 1 2 3
+g SecondGroup
+f 1 3 4";
+        let obj = parse_obj_file(file).unwrap();
+        let g1 = obj.group("FirstGroup").unwrap();
+        let g2 = obj.group("SecondGroup").unwrap();
+        assert_eq!(
+            vec![Face {
+                vertices: [0, 1, 2],
+                normals: None
+            }],
+            g1.faces
+        );
+        assert_eq!(
+            vec![Face {
+                vertices: [0, 2, 3],
+                normals: None
+            }],
+            g2.faces
+        );
+        assert!(obj.default_group().faces.is_empty());
+    }
+    #[test]
+    fn vertex_normal_records() {
+        let file = "vn 0 0 1
+vn 0.707 0 -0.707
+vn 1 2 3";
+        let obj = parse_obj_file(file).unwrap();
+        assert_eq!(Vec3::new(0.0, 0.0, 1.0), obj.normals[0]);
+        assert_eq!(Vec3::new(0.707, 0.0, -0.707), obj.normals[1]);
+        assert_eq!(Vec3::new(1.0, 2.0, 3.0), obj.normals[2]);
+    }
+    #[test]
+    fn faces_with_normals() {
+        let file = "v 0 1 0
+v -1 0 0
+v 1 0 0
+
+vn -1 0 0
+vn 1 0 0
+vn 0 1 0
+
+f 1//3 2//1 3//2
+f 1/0/3 2/102/1 3/14/2";
+        let obj = parse_obj_file(file).unwrap();
+        let g = obj.default_group();
+        let expected = Face {
+            vertices: [0, 1, 2],
+            normals: Some([2, 0, 1]),
+        };
+        assert_eq!(expected, g.faces[0]);
+        assert_eq!(expected, g.faces[1]);
+    }
+    #[test]
+    fn negative_indices_count_from_the_end() {
+        let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+f -3 -2 -1";
+        let obj = parse_obj_file(file).unwrap();
+        assert_eq!([0, 1, 2], obj.default_group().faces[0].vertices);
+    }
+    #[test]
+    fn a_malformed_vertex_is_an_error() {
+        let file = "v 1 0 0
+v 1 zero 0";
+        match parse_obj_file(file) {
+            Err(ObjError::Malformed { line, .. }) => assert_eq!(2, line),
+            other => panic!("Expected a malformed line error, got {:?}", other),
+        }
+    }
+    #[test]
+    fn a_face_with_too_few_vertices_is_an_error() {
+        let file = "v 1 0 0
+v 0 1 0
+f 1 2";
+        match parse_obj_file(file) {
+            Err(ObjError::Malformed { line, .. }) => assert_eq!(3, line),
+            other => panic!("Expected a malformed line error, got {:?}", other),
+        }
+    }
+    #[test]
+    fn a_face_referring_to_a_missing_vertex_is_an_error() {
+        let file = "v 1 0 0
+v 0 1 0
+f 1 2 3";
+        match parse_obj_file(file) {
+            Err(ObjError::InvalidIndex { line, index }) => {
+                assert_eq!(3, line);
+                assert_eq!(3, index);
+            }
+            other => panic!("Expected an invalid index error, got {:?}", other),
+        }
+    }
+    #[test]
+    fn reading_a_missing_file_is_an_error() {
+        match read_obj_file("/this/file/does/not/exist.obj") {
+            Err(ObjError::Io(_)) => (),
+            other => panic!("Expected an io error, got {:?}", other),
+        }
+    }
+    #[test]
+    fn converting_an_obj_file_to_a_group() {
+        let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+
+f 1 2 3
+g FirstGroup
+f 1 2 3
+g SecondGroup
+f 1 3 4";
+        let obj = parse_obj_file(file).unwrap();
+        let g = obj.to_group();
+        // One triangle from the default group and the two named groups
+        assert_eq!(3, g.children().len());
+    }
+    #[test]
+    fn an_obj_group_can_be_placed_with_a_transform() {
+        let file = "v 0 1 0
+v -1 0 0
+v 1 0 0
+vn 0 0 -1
+g Flat
+f 1 2 3
+g Smooth
+f 1//1 2//1 3//1";
+        let obj = parse_obj_file(file).unwrap();
+        let mut g = obj.to_group();
+        g.set_transform(translation(0.0, 0.0, 5.0));
+        let r = Ray::new(Point3::new(0.0, 0.5, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        let xs = g.intersect(r);
+        assert_eq!(2, xs.len());
+        for i in xs.iter() {
+            assert!((i.t - 5.0).abs() < 1e-5);
+            assert_eq!(&translation(0.0, 0.0, 5.0), i.object.parent_transform());
+        }
+    }
+}
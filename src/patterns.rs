@@ -0,0 +1,589 @@
+use crate::matrix::Matrix4;
+use crate::perlin::noise;
+use crate::shapes::{Shape, SingularTransform};
+use crate::transformations::Transform;
+use crate::tuple::{Color, Point3, Vec3};
+use std::fmt::Debug;
+
+// A color that varies over the surface of a shape. Implementors only deal with
+// pattern space, the conversion from world space is done in pattern_at_shape.
+pub trait Pattern: Debug + Send + Sync {
+    fn transform(&self) -> &Transform;
+    fn set_transform(&mut self, t: Matrix4);
+
+    // The point is already transformed to pattern space
+    fn pattern_at(&self, point: Point3) -> Color;
+
+    // The point is in the space of whatever contains the pattern, the object
+    // or for patterns used inside other patterns, the parent's pattern space.
+    // A singular transform leaves no point to sample, so the pattern is black.
+    fn pattern_at_parent(&self, parent_point: Point3) -> Color {
+        match self.transform().try_inverse() {
+            Ok(inverse) => self.pattern_at(inverse * parent_point),
+            Err(_) => Color::new(0.0, 0.0, 0.0),
+        }
+    }
+
+    // From world space through the object's transform to pattern space
+    fn pattern_at_shape(&self, object: &dyn Shape, world_point: Point3) -> Color {
+        self.pattern_at_parent(object.world_to_object(world_point))
+    }
+
+    // Patterns made of other patterns also check those. The path says where
+    // the pattern is, e.g. objects[0].material.pattern.a
+    fn check_transform(&self, path: &str) -> Result<(), SingularTransform> {
+        self.own_transform_check(path)
+    }
+
+    fn own_transform_check(&self, path: &str) -> Result<(), SingularTransform> {
+        if self.transform().is_singular() {
+            Err(SingularTransform::of::<Self>(path))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+// A single color, mostly useful as an input to the other patterns
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolidPattern {
+    pub color: Color,
+    pub transform: Transform,
+}
+
+impl SolidPattern {
+    pub fn new(color: Color) -> SolidPattern {
+        SolidPattern {
+            color,
+            transform: Transform::identity(),
+        }
+    }
+}
+
+impl Pattern for SolidPattern {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn set_transform(&mut self, t: Matrix4) {
+        self.transform = Transform::new(t);
+    }
+
+    fn pattern_at(&self, _point: Point3) -> Color {
+        self.color
+    }
+}
+
+// Alternates between two colors along x
+#[derive(Debug)]
+pub struct StripePattern {
+    pub a: Box<dyn Pattern>,
+    pub b: Box<dyn Pattern>,
+    pub transform: Transform,
+}
+
+impl StripePattern {
+    pub fn new(a: Color, b: Color) -> StripePattern {
+        StripePattern::nested(
+            Box::new(SolidPattern::new(a)),
+            Box::new(SolidPattern::new(b)),
+        )
+    }
+
+    pub fn nested(a: Box<dyn Pattern>, b: Box<dyn Pattern>) -> StripePattern {
+        StripePattern {
+            a,
+            b,
+            transform: Transform::identity(),
+        }
+    }
+}
+
+impl Pattern for StripePattern {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn set_transform(&mut self, t: Matrix4) {
+        self.transform = Transform::new(t);
+    }
+
+    fn check_transform(&self, path: &str) -> Result<(), SingularTransform> {
+        self.own_transform_check(path)?;
+        self.a.check_transform(&format!("{}.a", path))?;
+        self.b.check_transform(&format!("{}.b", path))
+    }
+
+    fn pattern_at(&self, point: Point3) -> Color {
+        if (point.0.floor() as i64) % 2 == 0 {
+            self.a.pattern_at_parent(point)
+        } else {
+            self.b.pattern_at_parent(point)
+        }
+    }
+}
+
+// Blends linearly from a to b between x = 0 and x = 1, then repeats
+#[derive(Debug)]
+pub struct GradientPattern {
+    pub a: Box<dyn Pattern>,
+    pub b: Box<dyn Pattern>,
+    pub transform: Transform,
+}
+
+impl GradientPattern {
+    pub fn new(a: Color, b: Color) -> GradientPattern {
+        GradientPattern::nested(
+            Box::new(SolidPattern::new(a)),
+            Box::new(SolidPattern::new(b)),
+        )
+    }
+
+    pub fn nested(a: Box<dyn Pattern>, b: Box<dyn Pattern>) -> GradientPattern {
+        GradientPattern {
+            a,
+            b,
+            transform: Transform::identity(),
+        }
+    }
+}
+
+impl Pattern for GradientPattern {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn set_transform(&mut self, t: Matrix4) {
+        self.transform = Transform::new(t);
+    }
+
+    fn check_transform(&self, path: &str) -> Result<(), SingularTransform> {
+        self.own_transform_check(path)?;
+        self.a.check_transform(&format!("{}.a", path))?;
+        self.b.check_transform(&format!("{}.b", path))
+    }
+
+    fn pattern_at(&self, point: Point3) -> Color {
+        let a = self.a.pattern_at_parent(point);
+        let b = self.b.pattern_at_parent(point);
+        let fraction = point.0 - point.0.floor();
+        a + (b - a) * fraction
+    }
+}
+
+// Concentric rings around the y axis
+#[derive(Debug)]
+pub struct RingPattern {
+    pub a: Box<dyn Pattern>,
+    pub b: Box<dyn Pattern>,
+    pub transform: Transform,
+}
+
+impl RingPattern {
+    pub fn new(a: Color, b: Color) -> RingPattern {
+        RingPattern::nested(
+            Box::new(SolidPattern::new(a)),
+            Box::new(SolidPattern::new(b)),
+        )
+    }
+
+    pub fn nested(a: Box<dyn Pattern>, b: Box<dyn Pattern>) -> RingPattern {
+        RingPattern {
+            a,
+            b,
+            transform: Transform::identity(),
+        }
+    }
+}
+
+impl Pattern for RingPattern {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn set_transform(&mut self, t: Matrix4) {
+        self.transform = Transform::new(t);
+    }
+
+    fn check_transform(&self, path: &str) -> Result<(), SingularTransform> {
+        self.own_transform_check(path)?;
+        self.a.check_transform(&format!("{}.a", path))?;
+        self.b.check_transform(&format!("{}.b", path))
+    }
+
+    fn pattern_at(&self, point: Point3) -> Color {
+        let distance = (point.0 * point.0 + point.2 * point.2).sqrt();
+        if (distance.floor() as i64) % 2 == 0 {
+            self.a.pattern_at_parent(point)
+        } else {
+            self.b.pattern_at_parent(point)
+        }
+    }
+}
+
+// Alternating cubes in all three dimensions
+#[derive(Debug)]
+pub struct CheckersPattern {
+    pub a: Box<dyn Pattern>,
+    pub b: Box<dyn Pattern>,
+    pub transform: Transform,
+}
+
+impl CheckersPattern {
+    pub fn new(a: Color, b: Color) -> CheckersPattern {
+        CheckersPattern::nested(
+            Box::new(SolidPattern::new(a)),
+            Box::new(SolidPattern::new(b)),
+        )
+    }
+
+    pub fn nested(a: Box<dyn Pattern>, b: Box<dyn Pattern>) -> CheckersPattern {
+        CheckersPattern {
+            a,
+            b,
+            transform: Transform::identity(),
+        }
+    }
+}
+
+impl Pattern for CheckersPattern {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn set_transform(&mut self, t: Matrix4) {
+        self.transform = Transform::new(t);
+    }
+
+    fn check_transform(&self, path: &str) -> Result<(), SingularTransform> {
+        self.own_transform_check(path)?;
+        self.a.check_transform(&format!("{}.a", path))?;
+        self.b.check_transform(&format!("{}.b", path))
+    }
+
+    fn pattern_at(&self, point: Point3) -> Color {
+        let sum = point.0.floor() + point.1.floor() + point.2.floor();
+        if (sum as i64) % 2 == 0 {
+            self.a.pattern_at_parent(point)
+        } else {
+            self.b.pattern_at_parent(point)
+        }
+    }
+}
+
+// The average of two patterns, e.g. two stripe patterns at right angles give a plaid
+#[derive(Debug)]
+pub struct BlendedPattern {
+    pub a: Box<dyn Pattern>,
+    pub b: Box<dyn Pattern>,
+    pub transform: Transform,
+}
+
+impl BlendedPattern {
+    pub fn new(a: Box<dyn Pattern>, b: Box<dyn Pattern>) -> BlendedPattern {
+        BlendedPattern {
+            a,
+            b,
+            transform: Transform::identity(),
+        }
+    }
+}
+
+impl Pattern for BlendedPattern {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn set_transform(&mut self, t: Matrix4) {
+        self.transform = Transform::new(t);
+    }
+
+    fn check_transform(&self, path: &str) -> Result<(), SingularTransform> {
+        self.own_transform_check(path)?;
+        self.a.check_transform(&format!("{}.a", path))?;
+        self.b.check_transform(&format!("{}.b", path))
+    }
+
+    fn pattern_at(&self, point: Point3) -> Color {
+        (self.a.pattern_at_parent(point) + self.b.pattern_at_parent(point)) * 0.5
+    }
+}
+
+// Jitters the point with Perlin noise before sampling the inner pattern,
+// which breaks up the straight lines to give marble or wood like patterns
+#[derive(Debug)]
+pub struct PerturbedPattern {
+    pub pattern: Box<dyn Pattern>,
+    // How far the point may be moved in each direction
+    pub scale: f64,
+    pub transform: Transform,
+}
+
+impl PerturbedPattern {
+    pub fn new(pattern: Box<dyn Pattern>, scale: f64) -> PerturbedPattern {
+        PerturbedPattern {
+            pattern,
+            scale,
+            transform: Transform::identity(),
+        }
+    }
+}
+
+impl Pattern for PerturbedPattern {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn set_transform(&mut self, t: Matrix4) {
+        self.transform = Transform::new(t);
+    }
+
+    fn check_transform(&self, path: &str) -> Result<(), SingularTransform> {
+        self.own_transform_check(path)?;
+        self.pattern.check_transform(&format!("{}.pattern", path))
+    }
+
+    fn pattern_at(&self, point: Point3) -> Color {
+        // Offset the noise lookups so each axis is jittered independently
+        let Point3(x, y, z) = point;
+        let jitter = Vec3::new(noise(x, y, z), noise(x, y, z + 1.0), noise(x, y, z + 2.0));
+        self.pattern.pattern_at_parent(point + jitter * self.scale)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+
+mod tests {
+    use super::*;
+    use crate::spheres::Sphere;
+    use crate::transformations::{rotation_y, scaling, translation};
+    use std::f64::consts::PI;
+
+    fn white() -> Color {
+        Color::new(1.0, 1.0, 1.0)
+    }
+
+    fn black() -> Color {
+        Color::new(0.0, 0.0, 0.0)
+    }
+
+    // Returns the pattern space point as a color, to check the transformations
+    #[derive(Debug)]
+    struct TestPattern {
+        transform: Transform,
+    }
+
+    impl Pattern for TestPattern {
+        fn transform(&self) -> &Transform {
+            &self.transform
+        }
+        fn set_transform(&mut self, t: Matrix4) {
+            self.transform = Transform::new(t);
+        }
+        fn pattern_at(&self, point: Point3) -> Color {
+            Color::new(point.0, point.1, point.2)
+        }
+    }
+
+    fn test_pattern() -> TestPattern {
+        TestPattern {
+            transform: Transform::identity(),
+        }
+    }
+
+    #[test]
+    fn creating_a_stripe_pattern() {
+        let pattern = StripePattern::new(white(), black());
+        let origin = Point3::new(0.0, 0.0, 0.0);
+        assert_eq!(white(), pattern.a.pattern_at(origin));
+        assert_eq!(black(), pattern.b.pattern_at(origin));
+    }
+    #[test]
+    fn a_stripe_pattern_is_constant_in_y() {
+        let pattern = StripePattern::new(white(), black());
+        assert_eq!(white(), pattern.pattern_at(Point3::new(0.0, 0.0, 0.0)));
+        assert_eq!(white(), pattern.pattern_at(Point3::new(0.0, 1.0, 0.0)));
+        assert_eq!(white(), pattern.pattern_at(Point3::new(0.0, 2.0, 0.0)));
+    }
+    #[test]
+    fn a_stripe_pattern_is_constant_in_z() {
+        let pattern = StripePattern::new(white(), black());
+        assert_eq!(white(), pattern.pattern_at(Point3::new(0.0, 0.0, 0.0)));
+        assert_eq!(white(), pattern.pattern_at(Point3::new(0.0, 0.0, 1.0)));
+        assert_eq!(white(), pattern.pattern_at(Point3::new(0.0, 0.0, 2.0)));
+    }
+    #[test]
+    fn a_stripe_pattern_alternates_in_x() {
+        let pattern = StripePattern::new(white(), black());
+        assert_eq!(white(), pattern.pattern_at(Point3::new(0.0, 0.0, 0.0)));
+        assert_eq!(white(), pattern.pattern_at(Point3::new(0.9, 0.0, 0.0)));
+        assert_eq!(black(), pattern.pattern_at(Point3::new(1.0, 0.0, 0.0)));
+        assert_eq!(black(), pattern.pattern_at(Point3::new(-0.1, 0.0, 0.0)));
+        assert_eq!(black(), pattern.pattern_at(Point3::new(-1.0, 0.0, 0.0)));
+        assert_eq!(white(), pattern.pattern_at(Point3::new(-1.1, 0.0, 0.0)));
+    }
+    #[test]
+    fn the_default_pattern_transformation() {
+        let pattern = test_pattern();
+        assert_eq!(&Matrix4::identity(), pattern.transform());
+    }
+    #[test]
+    fn assigning_a_transformation() {
+        let mut pattern = test_pattern();
+        pattern.set_transform(translation(1.0, 2.0, 3.0));
+        assert_eq!(&translation(1.0, 2.0, 3.0), pattern.transform());
+    }
+    #[test]
+    fn a_pattern_with_an_object_transformation() {
+        let mut shape = Sphere::default();
+        shape.set_transform(scaling(2.0, 2.0, 2.0));
+        let pattern = test_pattern();
+        let c = pattern.pattern_at_shape(&shape, Point3::new(2.0, 3.0, 4.0));
+        assert_eq!(Color::new(1.0, 1.5, 2.0), c);
+    }
+    #[test]
+    fn a_pattern_with_a_pattern_transformation() {
+        let shape = Sphere::default();
+        let mut pattern = test_pattern();
+        pattern.set_transform(scaling(2.0, 2.0, 2.0));
+        let c = pattern.pattern_at_shape(&shape, Point3::new(2.0, 3.0, 4.0));
+        assert_eq!(Color::new(1.0, 1.5, 2.0), c);
+    }
+    #[test]
+    fn a_pattern_with_a_singular_transformation_is_black() {
+        let shape = Sphere::default();
+        let mut pattern = StripePattern::new(white(), white());
+        pattern.set_transform(scaling(0.0, 1.0, 1.0));
+        let c = pattern.pattern_at_shape(&shape, Point3::new(2.0, 3.0, 4.0));
+        assert_eq!(black(), c);
+    }
+    #[test]
+    fn a_nested_pattern_with_a_singular_transformation_is_reported() {
+        let mut stripes = StripePattern::new(white(), black());
+        stripes.set_transform(scaling(1.0, 0.0, 1.0));
+        let pattern = BlendedPattern::new(
+            Box::new(SolidPattern::new(white())),
+            Box::new(PerturbedPattern::new(Box::new(stripes), 0.2)),
+        );
+        let error = pattern.check_transform("pattern").unwrap_err();
+        assert_eq!("StripePattern", error.shape);
+        assert_eq!("pattern.b.pattern", error.path);
+    }
+    #[test]
+    fn a_pattern_with_both_an_object_and_a_pattern_transformation() {
+        let mut shape = Sphere::default();
+        shape.set_transform(scaling(2.0, 2.0, 2.0));
+        let mut pattern = test_pattern();
+        pattern.set_transform(translation(0.5, 1.0, 1.5));
+        let c = pattern.pattern_at_shape(&shape, Point3::new(2.5, 3.0, 3.5));
+        assert_eq!(Color::new(0.75, 0.5, 0.25), c);
+    }
+    #[test]
+    fn a_gradient_linearly_interpolates_between_colors() {
+        let pattern = GradientPattern::new(white(), black());
+        assert_eq!(white(), pattern.pattern_at(Point3::new(0.0, 0.0, 0.0)));
+        assert_eq!(
+            Color::new(0.75, 0.75, 0.75),
+            pattern.pattern_at(Point3::new(0.25, 0.0, 0.0))
+        );
+        assert_eq!(
+            Color::new(0.5, 0.5, 0.5),
+            pattern.pattern_at(Point3::new(0.5, 0.0, 0.0))
+        );
+        assert_eq!(
+            Color::new(0.25, 0.25, 0.25),
+            pattern.pattern_at(Point3::new(0.75, 0.0, 0.0))
+        );
+    }
+    #[test]
+    fn a_ring_should_extend_in_both_x_and_z() {
+        let pattern = RingPattern::new(white(), black());
+        assert_eq!(white(), pattern.pattern_at(Point3::new(0.0, 0.0, 0.0)));
+        assert_eq!(black(), pattern.pattern_at(Point3::new(1.0, 0.0, 0.0)));
+        assert_eq!(black(), pattern.pattern_at(Point3::new(0.0, 0.0, 1.0)));
+        // 0.708 = just slightly more than sqrt(2)/2
+        assert_eq!(black(), pattern.pattern_at(Point3::new(0.708, 0.0, 0.708)));
+    }
+    #[test]
+    fn checkers_should_repeat_in_x() {
+        let pattern = CheckersPattern::new(white(), black());
+        assert_eq!(white(), pattern.pattern_at(Point3::new(0.0, 0.0, 0.0)));
+        assert_eq!(white(), pattern.pattern_at(Point3::new(0.99, 0.0, 0.0)));
+        assert_eq!(black(), pattern.pattern_at(Point3::new(1.01, 0.0, 0.0)));
+    }
+    #[test]
+    fn checkers_should_repeat_in_y() {
+        let pattern = CheckersPattern::new(white(), black());
+        assert_eq!(white(), pattern.pattern_at(Point3::new(0.0, 0.0, 0.0)));
+        assert_eq!(white(), pattern.pattern_at(Point3::new(0.0, 0.99, 0.0)));
+        assert_eq!(black(), pattern.pattern_at(Point3::new(0.0, 1.01, 0.0)));
+    }
+    #[test]
+    fn checkers_should_repeat_in_z() {
+        let pattern = CheckersPattern::new(white(), black());
+        assert_eq!(white(), pattern.pattern_at(Point3::new(0.0, 0.0, 0.0)));
+        assert_eq!(white(), pattern.pattern_at(Point3::new(0.0, 0.0, 0.99)));
+        assert_eq!(black(), pattern.pattern_at(Point3::new(0.0, 0.0, 1.01)));
+    }
+    #[test]
+    fn a_solid_pattern_is_the_same_everywhere() {
+        let pattern = SolidPattern::new(white());
+        assert_eq!(white(), pattern.pattern_at(Point3::new(0.0, 0.0, 0.0)));
+        assert_eq!(white(), pattern.pattern_at(Point3::new(-3.5, 2.1, 7.0)));
+    }
+    #[test]
+    fn a_nested_checkers_pattern_samples_its_inner_patterns() {
+        let red = Color::new(1.0, 0.0, 0.0);
+        let stripes = StripePattern::new(white(), black());
+        let pattern = CheckersPattern::nested(Box::new(stripes), Box::new(SolidPattern::new(red)));
+        // The first square has stripes, the next one along z is solid
+        assert_eq!(white(), pattern.pattern_at(Point3::new(0.5, 0.0, 0.5)));
+        assert_eq!(red, pattern.pattern_at(Point3::new(0.5, 0.0, 1.5)));
+        // x = 1.5, z = 1.5 is back on a striped square, on the second stripe
+        assert_eq!(black(), pattern.pattern_at(Point3::new(1.5, 0.0, 1.5)));
+    }
+    #[test]
+    fn a_nested_pattern_is_transformed_relative_to_its_parent() {
+        let mut stripes = StripePattern::new(white(), black());
+        stripes.set_transform(scaling(0.5, 0.5, 0.5));
+        let mut pattern = CheckersPattern::nested(Box::new(stripes), Box::new(test_pattern()));
+        pattern.set_transform(scaling(2.0, 2.0, 2.0));
+        // Parent space x = 0.6 is 1.2 in the stripes' space, the second stripe
+        assert_eq!(black(), pattern.pattern_at(Point3::new(0.6, 0.0, 0.0)));
+        // Through the shape, world x = 1.2 is 0.6 in the checkers' space
+        let shape = Sphere::default();
+        let c = pattern.pattern_at_shape(&shape, Point3::new(1.2, 0.0, 0.0));
+        assert_eq!(black(), c);
+    }
+    #[test]
+    fn a_blended_pattern_averages_two_patterns() {
+        let a = StripePattern::new(white(), black());
+        let mut b = StripePattern::new(white(), black());
+        b.set_transform(rotation_y(PI / 2.0));
+        let pattern = BlendedPattern::new(Box::new(a), Box::new(b));
+        assert_eq!(white(), pattern.pattern_at(Point3::new(0.5, 0.0, -0.5)));
+        assert_eq!(
+            Color::new(0.5, 0.5, 0.5),
+            pattern.pattern_at(Point3::new(1.5, 0.0, -0.5))
+        );
+        assert_eq!(black(), pattern.pattern_at(Point3::new(1.5, 0.0, 0.5)));
+    }
+    #[test]
+    fn a_perturbed_pattern_leaves_integer_points_unchanged() {
+        // The noise is zero at integer coordinates, so the point isn't moved
+        let pattern = PerturbedPattern::new(Box::new(test_pattern()), 1.0);
+        let c = pattern.pattern_at(Point3::new(1.0, 2.0, 3.0));
+        assert_eq!(Color::new(1.0, 2.0, 3.0), c);
+    }
+    #[test]
+    fn a_perturbed_pattern_jitters_the_point_within_its_scale() {
+        let pattern = PerturbedPattern::new(Box::new(test_pattern()), 0.2);
+        let p = Point3::new(0.3, 0.6, 0.9);
+        let c = pattern.pattern_at(p);
+        assert!(c != Color::new(0.3, 0.6, 0.9));
+        assert!((c.0 - p.0).abs() <= 0.2);
+        assert!((c.1 - p.1).abs() <= 0.2);
+        assert!((c.2 - p.2).abs() <= 0.2);
+    }
+}
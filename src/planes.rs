@@ -0,0 +1,140 @@
+use crate::intersections::{Intersection, EPSILON};
+use crate::materials::Material;
+use crate::matrix::Matrix4;
+use crate::rays::Ray;
+use crate::shapes::Shape;
+use crate::transformations::Transform;
+use crate::tuple::{Point3, Vec3};
+
+// An infinite plane, the xz plane in object space
+#[derive(Debug, PartialEq, Clone)]
+pub struct Plane {
+    pub transform: Transform,
+    pub material: Material,
+    parent_transform: Transform,
+}
+
+impl Default for Plane {
+    fn default() -> Plane {
+        Plane {
+            transform: Transform::identity(),
+            material: Material::default(),
+            parent_transform: Transform::identity(),
+        }
+    }
+}
+
+impl Shape for Plane {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn set_transform(&mut self, t: Matrix4) {
+        self.transform = Transform::new(t);
+    }
+
+    fn parent_transform(&self) -> &Transform {
+        &self.parent_transform
+    }
+
+    fn set_parent_transform(&mut self, t: Matrix4) {
+        self.parent_transform = Transform::new(t);
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_material(&mut self, m: Material) {
+        self.material = m;
+    }
+
+    fn local_intersect(&self, ray: Ray) -> Vec<Intersection<'_>> {
+        // A ray parallel to the plane (or coplanar with it) never hits it
+        if ray.direction.1.abs() < EPSILON {
+            return Vec::new();
+        }
+        let t = -ray.origin.1 / ray.direction.1;
+        vec![Intersection::new(t, self)]
+    }
+
+    fn local_normal_at(&self, _object_point: Point3) -> Vec3 {
+        Vec3::new(0.0, 1.0, 0.0)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+
+mod tests {
+    use super::*;
+    use crate::shapes::same_shape;
+    use crate::transformations::{rotation_z, translation};
+
+    #[test]
+    fn the_normal_of_a_plane_is_constant_everywhere() {
+        let p = Plane::default();
+        let n1 = p.local_normal_at(Point3::new(0.0, 0.0, 0.0));
+        let n2 = p.local_normal_at(Point3::new(10.0, 0.0, -10.0));
+        let n3 = p.local_normal_at(Point3::new(-5.0, 0.0, 150.0));
+        assert_eq!(Vec3::new(0.0, 1.0, 0.0), n1);
+        assert_eq!(Vec3::new(0.0, 1.0, 0.0), n2);
+        assert_eq!(Vec3::new(0.0, 1.0, 0.0), n3);
+    }
+    #[test]
+    fn intersect_with_a_ray_parallel_to_the_plane() {
+        let p = Plane::default();
+        let r = Ray::new(Point3::new(0.0, 10.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        let xs = p.local_intersect(r);
+        assert_eq!(0, xs.len());
+    }
+    #[test]
+    fn intersect_with_a_coplanar_ray() {
+        let p = Plane::default();
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        let xs = p.local_intersect(r);
+        assert_eq!(0, xs.len());
+    }
+    #[test]
+    fn a_ray_intersecting_a_plane_from_above() {
+        let p = Plane::default();
+        let r = Ray::new(Point3::new(0.0, 1.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        let xs = p.local_intersect(r);
+        assert_eq!(1, xs.len());
+        assert_eq!(1.0, xs[0].t);
+        assert!(same_shape(&p, xs[0].object));
+    }
+    #[test]
+    fn a_ray_intersecting_a_plane_from_below() {
+        let p = Plane::default();
+        let r = Ray::new(Point3::new(0.0, -1.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+        let xs = p.local_intersect(r);
+        assert_eq!(1, xs.len());
+        assert_eq!(1.0, xs[0].t);
+        assert!(same_shape(&p, xs[0].object));
+    }
+    #[test]
+    fn intersecting_a_translated_plane_with_a_ray() {
+        let mut p = Plane::default();
+        p.set_transform(translation(0.0, -2.0, 0.0));
+        let r = Ray::new(Point3::new(0.0, 1.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        let xs = p.intersect(r);
+        assert_eq!(1, xs.len());
+        assert_eq!(3.0, xs[0].t);
+    }
+    #[test]
+    fn the_normal_of_a_rotated_plane() {
+        let mut p = Plane::default();
+        p.set_transform(rotation_z(std::f64::consts::PI / 2.0));
+        let n = p.normal_at(Point3::new(0.0, 0.0, 0.0));
+        assert_eq!(Vec3::new(-1.0, 0.0, 0.0), n);
+    }
+    #[test]
+    fn a_plane_may_be_assigned_a_material() {
+        let mut p = Plane::default();
+        let mut m = Material::default();
+        m.ambient = 1.0;
+        p.set_material(m.clone());
+        assert_eq!(m, p.material);
+    }
+}
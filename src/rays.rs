@@ -1,29 +1,28 @@
-use crate::matrix::Matrix;
-use crate::tuple::Tuple;
+use crate::matrix::Matrix4;
+use crate::tuple::{Point3, Vec3};
 
 #[derive(Clone, Debug)]
 pub struct Ray {
-  pub origin: Tuple,
-  pub direction: Tuple,
+  pub origin: Point3,
+  pub direction: Vec3,
 }
 
 impl Ray {
-  pub fn new(o: Tuple, d: Tuple) -> Ray {
+  pub fn new(o: Point3, d: Vec3) -> Ray {
     Ray {
       origin: o,
       direction: d,
     }
   }
 
-  pub fn position(&self, t: f64) -> Tuple {
+  pub fn position(&self, t: f64) -> Point3 {
     self.origin + self.direction * t
   }
 
-  pub fn transform(&self, m: Matrix) -> Ray {
+  pub fn transform(&self, m: &Matrix4) -> Ray {
     Ray {
-      //TODO:Avoid clone?
-      origin: m.clone() * self.origin,
-      direction: m.clone() * self.direction,
+      origin: m * self.origin,
+      direction: m * self.direction,
     }
   }
 }
@@ -33,24 +32,23 @@ impl Ray {
 mod tests {
   use super::*;
   use crate::transformations::{scaling, translation};
-  use crate::tuple::Tuple;
   #[test]
   fn creating_and_querying_a_ray() {
-    let origin = Tuple::point(1.0, 2.0, 3.0);
-    let direction = Tuple::vector(4.0, 5.0, 6.0);
+    let origin = Point3::new(1.0, 2.0, 3.0);
+    let direction = Vec3::new(4.0, 5.0, 6.0);
     let r = Ray::new(origin, direction);
     assert_eq!(origin, r.origin);
     assert_eq!(direction, r.direction);
   }
   #[test]
   fn computing_a_point_from_a_distance() {
-    let origin = Tuple::point(2.0, 3.0, 4.0);
-    let direction = Tuple::vector(1.0, 0.0, 0.0);
+    let origin = Point3::new(2.0, 3.0, 4.0);
+    let direction = Vec3::new(1.0, 0.0, 0.0);
     let r = Ray::new(origin, direction);
-    let e0 = Tuple::point(2.0, 3.0, 4.0);
-    let e1 = Tuple::point(3.0, 3.0, 4.0);
-    let e2 = Tuple::point(1.0, 3.0, 4.0);
-    let e3 = Tuple::point(4.5, 3.0, 4.0);
+    let e0 = Point3::new(2.0, 3.0, 4.0);
+    let e1 = Point3::new(3.0, 3.0, 4.0);
+    let e2 = Point3::new(1.0, 3.0, 4.0);
+    let e3 = Point3::new(4.5, 3.0, 4.0);
     let p0 = r.position(0.0);
     let p1 = r.position(1.0);
     let p2 = r.position(-1.0);
@@ -62,26 +60,25 @@ mod tests {
   }
   #[test]
   fn translating_a_ray() {
-    let origin = Tuple::point(1.0, 2.0, 3.0);
-    let direction = Tuple::vector(0.0, 1.0, 0.0);
+    let origin = Point3::new(1.0, 2.0, 3.0);
+    let direction = Vec3::new(0.0, 1.0, 0.0);
     let r = Ray::new(origin, direction);
     let m = translation(3.0, 4.0, 5.0);
-    let r2 = r.transform(m);
-    let expected_moved_origin = Tuple::point(4.0, 6.0, 8.0);
+    let r2 = r.transform(&m);
+    let expected_moved_origin = Point3::new(4.0, 6.0, 8.0);
     assert_eq!(expected_moved_origin, r2.origin);
     assert_eq!(direction, r2.direction);
   }
   #[test]
   fn scaling_a_ray() {
-    let origin = Tuple::point(1.0, 2.0, 3.0);
-    let direction = Tuple::vector(0.0, 1.0, 0.0);
+    let origin = Point3::new(1.0, 2.0, 3.0);
+    let direction = Vec3::new(0.0, 1.0, 0.0);
     let r = Ray::new(origin, direction);
     let m = scaling(2.0, 3.0, 4.0);
-    let r2 = r.transform(m);
-    let expected_moved_origin = Tuple::point(2.0, 6.0, 12.0);
-    let expected_new_direction = Tuple::vector(0.0, 3.0, 0.0);
+    let r2 = r.transform(&m);
+    let expected_moved_origin = Point3::new(2.0, 6.0, 12.0);
+    let expected_new_direction = Vec3::new(0.0, 3.0, 0.0);
     assert_eq!(expected_moved_origin, r2.origin);
     assert_eq!(expected_new_direction, r2.direction);
   }
-
 }
@@ -0,0 +1,304 @@
+use crate::intersections::Intersection;
+use crate::materials::Material;
+use crate::matrix::Matrix4;
+use crate::rays::Ray;
+use crate::transformations::Transform;
+use crate::tuple::{Point3, Vec3};
+use std::fmt;
+use std::fmt::Debug;
+
+// Common behaviour for all geometry. Implementors only deal with object space,
+// the conversion from and to world space is done once in the provided methods.
+pub trait Shape: Debug + Send + Sync {
+    fn transform(&self) -> &Transform;
+    fn set_transform(&mut self, t: Matrix4);
+    // The combined transform of all groups containing the shape, set by the group
+    fn parent_transform(&self) -> &Transform;
+    fn set_parent_transform(&mut self, t: Matrix4);
+    fn material(&self) -> &Material;
+    fn set_material(&mut self, m: Material);
+
+    // The ray is already transformed to object space
+    fn local_intersect(&self, ray: Ray) -> Vec<Intersection<'_>>;
+    // The point is already transformed to object space
+    fn local_normal_at(&self, point: Point3) -> Vec3;
+    // Shapes that interpolate their normal across the surface override this to use u/v of the hit
+    fn local_normal_at_hit(&self, point: Point3, _hit: &Intersection) -> Vec3 {
+        self.local_normal_at(point)
+    }
+
+    // A ray reaching a shape inside a group is already in the group's space,
+    // so only the shape's own transform is applied here
+    // A singular transform flattens the shape to nothing a ray can hit
+    fn intersect(&self, ray: Ray) -> Vec<Intersection<'_>> {
+        match self.transform().try_inverse() {
+            Ok(inverse) => self.local_intersect(ray.transform(inverse)),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    // From world space through all parents to object space
+    fn world_to_object(&self, world_point: Point3) -> Point3 {
+        let parent_point = self.parent_transform().inverse() * world_point;
+        self.transform().inverse() * parent_point
+    }
+
+    // From object space through all parents to world space
+    fn normal_to_world(&self, local_normal: Vec3) -> Vec3 {
+        let parent_normal = transform_normal(self.transform(), local_normal);
+        transform_normal(self.parent_transform(), parent_normal)
+    }
+
+    fn normal_at(&self, world_point: Point3) -> Vec3 {
+        let local_point = self.world_to_object(world_point);
+        let local_normal = self.local_normal_at(local_point);
+        self.normal_to_world(local_normal)
+    }
+
+    fn normal_at_hit(&self, world_point: Point3, hit: &Intersection) -> Vec3 {
+        let local_point = self.world_to_object(world_point);
+        let local_normal = self.local_normal_at_hit(local_point, hit);
+        self.normal_to_world(local_normal)
+    }
+
+    // Groups and CSGs also check all their descendants. The path says where
+    // the shape is in the world, e.g. objects[2].children[1]
+    fn check_transform(&self, path: &str) -> Result<(), SingularTransform> {
+        self.own_transform_check(path)
+    }
+
+    // Only the shape's own transform and the pattern of its material,
+    // without any descendants
+    fn own_transform_check(&self, path: &str) -> Result<(), SingularTransform> {
+        if self.transform().is_singular() {
+            return Err(SingularTransform::of::<Self>(path));
+        }
+        match &self.material().pattern {
+            Some(pattern) => pattern.check_transform(&format!("{}.material.pattern", path)),
+            None => Ok(()),
+        }
+    }
+
+    // True if other is this shape, or for groups and CSGs one of the descendants
+    fn includes(&self, other: &dyn Shape) -> bool {
+        std::ptr::eq(
+            self as *const Self as *const u8,
+            other as *const dyn Shape as *const u8,
+        )
+    }
+}
+
+// A shape or pattern whose transform can not be inverted, by its type and
+// where it is in the world
+#[derive(Debug, Clone, PartialEq)]
+pub struct SingularTransform {
+    pub shape: String,
+    pub path: String,
+}
+
+impl SingularTransform {
+    pub(crate) fn of<T: ?Sized>(path: &str) -> SingularTransform {
+        let name = std::any::type_name::<T>();
+        SingularTransform {
+            shape: name.rsplit("::").next().unwrap_or(name).to_string(),
+            path: path.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for SingularTransform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} at {} has a singular transform",
+            self.shape, self.path
+        )
+    }
+}
+
+impl std::error::Error for SingularTransform {}
+
+// Normals are transformed with the inverse transpose to stay perpendicular to the surface.
+// Only the upper 3x3 part applies to a vector, so the w the book resets never shows up.
+fn transform_normal(transform: &Transform, normal: Vec3) -> Vec3 {
+    (transform.inverse_transpose() * normal).normalize()
+}
+
+// Shapes are compared by identity, two equal spheres are still different objects
+pub fn same_shape(a: &dyn Shape, b: &dyn Shape) -> bool {
+    std::ptr::eq(
+        a as *const dyn Shape as *const u8,
+        b as *const dyn Shape as *const u8,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transformations::{rotation_y, rotation_z, scaling, translation};
+    use std::f64::consts::FRAC_1_SQRT_2;
+    use std::sync::Mutex;
+
+    #[derive(Debug)]
+    struct TestShape {
+        transform: Transform,
+        material: Material,
+        parent_transform: Transform,
+        saved_ray: Mutex<Option<Ray>>,
+    }
+
+    impl TestShape {
+        fn new() -> TestShape {
+            TestShape {
+                transform: Transform::identity(),
+                material: Material::default(),
+                parent_transform: Transform::identity(),
+                saved_ray: Mutex::new(None),
+            }
+        }
+    }
+
+    impl Shape for TestShape {
+        fn transform(&self) -> &Transform {
+            &self.transform
+        }
+        fn set_transform(&mut self, t: Matrix4) {
+            self.transform = Transform::new(t);
+        }
+        fn parent_transform(&self) -> &Transform {
+            &self.parent_transform
+        }
+        fn set_parent_transform(&mut self, t: Matrix4) {
+            self.parent_transform = Transform::new(t);
+        }
+        fn material(&self) -> &Material {
+            &self.material
+        }
+        fn set_material(&mut self, m: Material) {
+            self.material = m;
+        }
+        fn local_intersect(&self, ray: Ray) -> Vec<Intersection<'_>> {
+            *self.saved_ray.lock().unwrap() = Some(ray);
+            Vec::new()
+        }
+        fn local_normal_at(&self, point: Point3) -> Vec3 {
+            point - Point3::origin()
+        }
+    }
+
+    #[test]
+    fn a_shape_with_a_singular_transform_is_never_hit() {
+        let mut s = TestShape::new();
+        s.set_transform(scaling(0.0, 1.0, 1.0));
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        assert!(s.intersect(r).is_empty());
+        assert!(s.saved_ray.lock().unwrap().is_none());
+        let error = s.check_transform("objects[0]").unwrap_err();
+        assert_eq!("TestShape", error.shape);
+        assert_eq!("objects[0]", error.path);
+        assert!(TestShape::new().check_transform("objects[0]").is_ok());
+    }
+    #[test]
+    fn the_default_transformation() {
+        let s = TestShape::new();
+        assert_eq!(&Matrix4::identity(), s.transform());
+    }
+    #[test]
+    fn assigning_a_transformation() {
+        let mut s = TestShape::new();
+        s.set_transform(translation(2.0, 3.0, 4.0));
+        assert_eq!(&translation(2.0, 3.0, 4.0), s.transform());
+    }
+    #[test]
+    fn the_default_material() {
+        let s = TestShape::new();
+        assert_eq!(&Material::default(), s.material());
+    }
+    #[test]
+    fn assigning_a_material() {
+        let mut s = TestShape::new();
+        let mut m = Material::default();
+        m.ambient = 1.0;
+        s.set_material(m.clone());
+        assert_eq!(&m, s.material());
+    }
+    #[test]
+    fn intersecting_a_scaled_shape_with_a_ray() {
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let mut s = TestShape::new();
+        s.set_transform(scaling(2.0, 2.0, 2.0));
+        s.intersect(r);
+        let saved_ray = s.saved_ray.lock().unwrap().clone().unwrap();
+        assert_eq!(Point3::new(0.0, 0.0, -2.5), saved_ray.origin);
+        assert_eq!(Vec3::new(0.0, 0.0, 0.5), saved_ray.direction);
+    }
+    #[test]
+    fn intersecting_a_translated_shape_with_a_ray() {
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let mut s = TestShape::new();
+        s.set_transform(translation(5.0, 0.0, 0.0));
+        s.intersect(r);
+        let saved_ray = s.saved_ray.lock().unwrap().clone().unwrap();
+        assert_eq!(Point3::new(-5.0, 0.0, -5.0), saved_ray.origin);
+        assert_eq!(Vec3::new(0.0, 0.0, 1.0), saved_ray.direction);
+    }
+    #[test]
+    fn computing_the_normal_on_a_translated_shape() {
+        let mut s = TestShape::new();
+        s.set_transform(translation(0.0, 1.0, 0.0));
+        let n = s.normal_at(Point3::new(0.0, 1.0 + FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+        assert_eq!(Vec3::new(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2), n);
+    }
+    #[test]
+    fn computing_the_normal_on_a_transformed_shape() {
+        let mut s = TestShape::new();
+        s.set_transform(scaling(1.0, 0.5, 1.0) * rotation_z(std::f64::consts::PI / 5.0));
+        let n = s.normal_at(Point3::new(0.0, 2_f64.sqrt() / 2.0, -(2_f64.sqrt()) / 2.0));
+        assert_eq!(Vec3::new(0.0, 0.97014, -0.24254), n);
+    }
+    #[test]
+    fn a_shape_has_no_parent_transform_by_default() {
+        let s = TestShape::new();
+        assert_eq!(&Matrix4::identity(), s.parent_transform());
+    }
+    #[test]
+    fn converting_a_point_from_world_to_object_space() {
+        let mut s = TestShape::new();
+        s.set_transform(translation(5.0, 0.0, 0.0));
+        // As if inside a group rotated around y, inside a group scaled by 2
+        s.set_parent_transform(rotation_y(std::f64::consts::PI / 2.0) * scaling(2.0, 2.0, 2.0));
+        let p = s.world_to_object(Point3::new(-2.0, 0.0, -10.0));
+        assert_eq!(Point3::new(0.0, 0.0, -1.0), p);
+    }
+    #[test]
+    fn converting_a_normal_from_object_to_world_space() {
+        let mut s = TestShape::new();
+        s.set_transform(translation(5.0, 0.0, 0.0));
+        s.set_parent_transform(rotation_y(std::f64::consts::PI / 2.0) * scaling(1.0, 2.0, 3.0));
+        let v = 3_f64.sqrt() / 3.0;
+        let n = s.normal_to_world(Vec3::new(v, v, v));
+        assert_eq!(Vec3::new(2.0 / 7.0, 3.0 / 7.0, -6.0 / 7.0), n);
+    }
+    #[test]
+    fn finding_the_normal_on_a_child_object() {
+        let mut s = TestShape::new();
+        s.set_transform(translation(5.0, 0.0, 0.0));
+        s.set_parent_transform(rotation_y(std::f64::consts::PI / 2.0) * scaling(1.0, 2.0, 3.0));
+        let n = s.normal_at(Point3::new(1.7321, 1.1547, -5.5774));
+        assert_eq!(Vec3::new(0.28570, 0.42854, -0.85716), n);
+    }
+    #[test]
+    fn shapes_are_compared_by_identity() {
+        let a = TestShape::new();
+        let b = TestShape::new();
+        assert!(same_shape(&a, &a));
+        assert!(!same_shape(&a, &b));
+    }
+    #[test]
+    fn a_shape_includes_only_itself() {
+        let a = TestShape::new();
+        let b = TestShape::new();
+        assert!(a.includes(&a));
+        assert!(!a.includes(&b));
+    }
+}
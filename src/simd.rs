@@ -0,0 +1,210 @@
+// Kernels for the hot tuple and matrix math, working on [x, y, z, w] lanes.
+// With the simd feature on x86_64 they use SSE2, which every x86_64 cpu has,
+// and AVX for the matrix multiply when it is enabled at compile time
+// (RUSTFLAGS="-C target-cpu=native"). Everywhere else the scalar path is used.
+
+pub type Lanes = [f64; 4];
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+pub use self::x86::*;
+
+#[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+pub use self::scalar::*;
+
+// With the simd feature the scalar path is only the reference for the tests
+#[cfg_attr(all(feature = "simd", target_arch = "x86_64"), allow(dead_code))]
+pub mod scalar {
+    use super::Lanes;
+
+    pub fn add(a: Lanes, b: Lanes) -> Lanes {
+        [a[0] + b[0], a[1] + b[1], a[2] + b[2], a[3] + b[3]]
+    }
+
+    pub fn sub(a: Lanes, b: Lanes) -> Lanes {
+        [a[0] - b[0], a[1] - b[1], a[2] - b[2], a[3] - b[3]]
+    }
+
+    pub fn dot(a: Lanes, b: Lanes) -> f64 {
+        a[0] * b[0] + a[1] * b[1] + a[2] * b[2] + a[3] * b[3]
+    }
+
+    // Only x, y and z take part, w is 0 in the result
+    pub fn cross(a: Lanes, b: Lanes) -> Lanes {
+        [
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+            0.0,
+        ]
+    }
+
+    pub fn normalize(a: Lanes) -> Lanes {
+        let m = dot(a, a).sqrt();
+        [a[0] / m, a[1] / m, a[2] / m, a[3] / m]
+    }
+
+    pub fn mul_matrix(m: &[Lanes; 4], t: Lanes) -> Lanes {
+        [dot(m[0], t), dot(m[1], t), dot(m[2], t), dot(m[3], t)]
+    }
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod x86 {
+    use super::Lanes;
+    use std::arch::x86_64::*;
+
+    // The lanes are kept as two registers, (x, y) and (z, w)
+    unsafe fn load(a: &Lanes) -> (__m128d, __m128d) {
+        (_mm_loadu_pd(a.as_ptr()), _mm_loadu_pd(a.as_ptr().add(2)))
+    }
+
+    unsafe fn store(lo: __m128d, hi: __m128d) -> Lanes {
+        let mut r = [0.0; 4];
+        _mm_storeu_pd(r.as_mut_ptr(), lo);
+        _mm_storeu_pd(r.as_mut_ptr().add(2), hi);
+        r
+    }
+
+    unsafe fn dot_pd(a: &Lanes, b: &Lanes) -> __m128d {
+        let (a_xy, a_zw) = load(a);
+        let (b_xy, b_zw) = load(b);
+        let s = _mm_add_pd(_mm_mul_pd(a_xy, b_xy), _mm_mul_pd(a_zw, b_zw));
+        _mm_add_sd(s, _mm_unpackhi_pd(s, s))
+    }
+
+    pub fn add(a: Lanes, b: Lanes) -> Lanes {
+        unsafe {
+            let (a_xy, a_zw) = load(&a);
+            let (b_xy, b_zw) = load(&b);
+            store(_mm_add_pd(a_xy, b_xy), _mm_add_pd(a_zw, b_zw))
+        }
+    }
+
+    pub fn sub(a: Lanes, b: Lanes) -> Lanes {
+        unsafe {
+            let (a_xy, a_zw) = load(&a);
+            let (b_xy, b_zw) = load(&b);
+            store(_mm_sub_pd(a_xy, b_xy), _mm_sub_pd(a_zw, b_zw))
+        }
+    }
+
+    pub fn dot(a: Lanes, b: Lanes) -> f64 {
+        unsafe { _mm_cvtsd_f64(dot_pd(&a, &b)) }
+    }
+
+    pub fn cross(a: Lanes, b: Lanes) -> Lanes {
+        unsafe {
+            let (a_xy, a_zw) = load(&a);
+            let (b_xy, b_zw) = load(&b);
+            // (y, z) and (z, x) of each operand
+            let a_yz = _mm_shuffle_pd(a_xy, a_zw, 0b01);
+            let b_yz = _mm_shuffle_pd(b_xy, b_zw, 0b01);
+            let a_zx = _mm_unpacklo_pd(a_zw, a_xy);
+            let b_zx = _mm_unpacklo_pd(b_zw, b_xy);
+            let xy = _mm_sub_pd(_mm_mul_pd(a_yz, b_zx), _mm_mul_pd(a_zx, b_yz));
+            let z = _mm_sub_sd(
+                _mm_mul_sd(a_xy, _mm_unpackhi_pd(b_xy, b_xy)),
+                _mm_mul_sd(_mm_unpackhi_pd(a_xy, a_xy), b_xy),
+            );
+            store(xy, _mm_move_sd(_mm_setzero_pd(), z))
+        }
+    }
+
+    pub fn normalize(a: Lanes) -> Lanes {
+        unsafe {
+            let d = dot_pd(&a, &a);
+            let m = _mm_sqrt_pd(_mm_unpacklo_pd(d, d));
+            let (xy, zw) = load(&a);
+            store(_mm_div_pd(xy, m), _mm_div_pd(zw, m))
+        }
+    }
+
+    #[cfg(not(target_feature = "avx"))]
+    pub fn mul_matrix(m: &[Lanes; 4], t: Lanes) -> Lanes {
+        unsafe {
+            let x = _mm_unpacklo_pd(dot_pd(&m[0], &t), dot_pd(&m[1], &t));
+            let y = _mm_unpacklo_pd(dot_pd(&m[2], &t), dot_pd(&m[3], &t));
+            store(x, y)
+        }
+    }
+
+    // Each row is multiplied in one register and the four horizontal sums
+    // are gathered into the result with two hadds and a cross-lane shuffle
+    #[cfg(target_feature = "avx")]
+    pub fn mul_matrix(m: &[Lanes; 4], t: Lanes) -> Lanes {
+        unsafe {
+            let t = _mm256_loadu_pd(t.as_ptr());
+            let p0 = _mm256_mul_pd(_mm256_loadu_pd(m[0].as_ptr()), t);
+            let p1 = _mm256_mul_pd(_mm256_loadu_pd(m[1].as_ptr()), t);
+            let p2 = _mm256_mul_pd(_mm256_loadu_pd(m[2].as_ptr()), t);
+            let p3 = _mm256_mul_pd(_mm256_loadu_pd(m[3].as_ptr()), t);
+            let h01 = _mm256_hadd_pd(p0, p1);
+            let h23 = _mm256_hadd_pd(p2, p3);
+            let swapped = _mm256_permute2f128_pd(h01, h23, 0x21);
+            let blended = _mm256_blend_pd(h01, h23, 0b1100);
+            let mut r = [0.0; 4];
+            _mm256_storeu_pd(r.as_mut_ptr(), _mm256_add_pd(swapped, blended));
+            r
+        }
+    }
+}
+
+// Without the simd feature both sides would be the scalar code
+#[cfg(all(test, feature = "simd", target_arch = "x86_64"))]
+#[allow(clippy::float_cmp)]
+
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn lanes() -> impl Strategy<Value = Lanes> {
+        prop::array::uniform4(-1.0e3..1.0e3)
+    }
+
+    // Summing in a different order may move the result a few ulps
+    fn close(a: f64, b: f64, scale: f64) -> bool {
+        (a - b).abs() <= 1e-12 * scale.max(1.0)
+    }
+
+    proptest! {
+        #[test]
+        fn add_matches_scalar(a in lanes(), b in lanes()) {
+            prop_assert_eq!(scalar::add(a, b), add(a, b));
+        }
+
+        #[test]
+        fn sub_matches_scalar(a in lanes(), b in lanes()) {
+            prop_assert_eq!(scalar::sub(a, b), sub(a, b));
+        }
+
+        #[test]
+        fn dot_matches_scalar(a in lanes(), b in lanes()) {
+            let scale: f64 = (0..4).map(|i| (a[i] * b[i]).abs()).sum();
+            prop_assert!(close(scalar::dot(a, b), dot(a, b), scale));
+        }
+
+        #[test]
+        fn cross_matches_scalar(a in lanes(), b in lanes()) {
+            prop_assert_eq!(scalar::cross(a, b), cross(a, b));
+        }
+
+        #[test]
+        fn normalize_matches_scalar(a in lanes()) {
+            prop_assume!(scalar::dot(a, a) > 1e-6);
+            let (expected, n) = (scalar::normalize(a), normalize(a));
+            for i in 0..4 {
+                prop_assert!(close(expected[i], n[i], 1.0));
+            }
+        }
+
+        #[test]
+        fn mul_matrix_matches_scalar(r0 in lanes(), r1 in lanes(), r2 in lanes(), r3 in lanes(), t in lanes()) {
+            let m = [r0, r1, r2, r3];
+            let (expected, p) = (scalar::mul_matrix(&m, t), mul_matrix(&m, t));
+            for i in 0..4 {
+                let scale: f64 = (0..4).map(|j| (m[i][j] * t[j]).abs()).sum();
+                prop_assert!(close(expected[i], p[i], scale));
+            }
+        }
+    }
+}
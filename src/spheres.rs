@@ -1,28 +1,66 @@
 use crate::intersections::*;
 use crate::materials::Material;
-use crate::matrix::Matrix;
+use crate::matrix::Matrix4;
 use crate::rays::Ray;
-use crate::tuple::Tuple;
+use crate::shapes::Shape;
+use crate::transformations::Transform;
+use crate::tuple::{Point3, Vec3};
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Sphere {
-  origin: Tuple,
+  origin: Point3,
   radius: f64,
-  pub transform: Matrix,
+  pub transform: Transform,
   pub material: Material,
+  parent_transform: Transform,
 }
 
 impl Sphere {
   pub fn default() -> Sphere {
     Sphere {
-      origin: Tuple::point(0.0, 0.0, 0.0),
+      origin: Point3::new(0.0, 0.0, 0.0),
       radius: 1.0,
-      transform: Matrix::identity(),
+      transform: Transform::identity(),
       material: Material::default(),
+      parent_transform: Transform::identity(),
     }
   }
-  pub fn intersect(&self, in_ray: Ray) -> Vec<Intersection> {
-    let ray = in_ray.transform(self.transform.clone().inverse());
+
+  // A fully transparent sphere with the refractive index of glass
+  pub fn glass() -> Sphere {
+    let mut s = Sphere::default();
+    s.material.transparency = 1.0;
+    s.material.refractive_index = 1.5;
+    s
+  }
+}
+
+impl Shape for Sphere {
+  fn transform(&self) -> &Transform {
+    &self.transform
+  }
+
+  fn set_transform(&mut self, t: Matrix4) {
+    self.transform = Transform::new(t);
+  }
+
+  fn parent_transform(&self) -> &Transform {
+    &self.parent_transform
+  }
+
+  fn set_parent_transform(&mut self, t: Matrix4) {
+    self.parent_transform = Transform::new(t);
+  }
+
+  fn material(&self) -> &Material {
+    &self.material
+  }
+
+  fn set_material(&mut self, m: Material) {
+    self.material = m;
+  }
+
+  fn local_intersect(&self, ray: Ray) -> Vec<Intersection<'_>> {
     let mut v: Vec<Intersection> = Vec::new();
 
     let sphere_to_ray = ray.origin - self.origin;
@@ -42,21 +80,8 @@ impl Sphere {
     v
   }
 
-  pub fn set_transform(&mut self, t: Matrix) {
-    self.transform = t;
-  }
-
-  pub fn normal_at(&self, world_point: Tuple) -> Tuple {
-    // (p - Tuple::point(0.0, 0.0, 0.0)).normalize()
-    let object_point = self.transform.inverse() * world_point;
-    let object_normal = object_point - self.origin;
-    let mut world_normal = self.transform.inverse().transpose() * object_normal;
-    world_normal.set_w(0.0);
-    world_normal.normalize()
-  }
-
-  pub fn set_material(&mut self, m: Material) {
-    self.material = m;
+  fn local_normal_at(&self, object_point: Point3) -> Vec3 {
+    object_point - self.origin
   }
 }
 
@@ -67,14 +92,13 @@ impl Sphere {
 mod tests {
   use super::*;
   use crate::materials::Material;
-  use crate::matrix::Matrix;
+  use crate::matrix::Matrix4;
   use crate::transformations::{rotation_z, scaling, translation};
-  use crate::tuple::Tuple;
 
   #[test]
   fn a_ray_intersects_a_sphere_at_two_points() {
-    let origin = Tuple::point(0.0, 0.0, -5.0);
-    let direction = Tuple::vector(0.0, 0.0, 1.0);
+    let origin = Point3::new(0.0, 0.0, -5.0);
+    let direction = Vec3::new(0.0, 0.0, 1.0);
     let r = Ray::new(origin, direction);
     let s = Sphere::default();
     let xs = s.intersect(r);
@@ -84,8 +108,8 @@ mod tests {
   }
   #[test]
   fn a_ray_intersects_a_sphere_at_a_tangent() {
-    let origin = Tuple::point(0.0, 1.0, -5.0);
-    let direction = Tuple::vector(0.0, 0.0, 1.0);
+    let origin = Point3::new(0.0, 1.0, -5.0);
+    let direction = Vec3::new(0.0, 0.0, 1.0);
     let r = Ray::new(origin, direction);
     let s = Sphere::default();
     let xs = s.intersect(r);
@@ -95,8 +119,8 @@ mod tests {
   }
   #[test]
   fn a_ray_intersects_misses_a_sphere() {
-    let origin = Tuple::point(0.0, 2.0, -5.0);
-    let direction = Tuple::vector(0.0, 0.0, 1.0);
+    let origin = Point3::new(0.0, 2.0, -5.0);
+    let direction = Vec3::new(0.0, 0.0, 1.0);
     let r = Ray::new(origin, direction);
     let s = Sphere::default();
     let xs = s.intersect(r);
@@ -104,8 +128,8 @@ mod tests {
   }
   #[test]
   fn a_ray_originates_inside_a_sphere() {
-    let origin = Tuple::point(0.0, 0.0, 0.0);
-    let direction = Tuple::vector(0.0, 0.0, 1.0);
+    let origin = Point3::new(0.0, 0.0, 0.0);
+    let direction = Vec3::new(0.0, 0.0, 1.0);
     let r = Ray::new(origin, direction);
     let s = Sphere::default();
     let xs = s.intersect(r);
@@ -115,8 +139,8 @@ mod tests {
   }
   #[test]
   fn a_sphere_is_behind_a_ray() {
-    let origin = Tuple::point(0.0, 0.0, 5.0);
-    let direction = Tuple::vector(0.0, 0.0, 1.0);
+    let origin = Point3::new(0.0, 0.0, 5.0);
+    let direction = Vec3::new(0.0, 0.0, 1.0);
     let r = Ray::new(origin, direction);
     let s = Sphere::default();
     let xs = s.intersect(r);
@@ -127,20 +151,19 @@ mod tests {
   #[test]
   fn a_spheres_default_transformation() {
     let s = Sphere::default();
-    assert_eq!(Matrix::identity(), s.transform);
+    assert_eq!(Matrix4::identity(), s.transform);
   }
   #[test]
   fn changing_a_spheres_transformation() {
     let mut s = Sphere::default();
     let t = translation(2.0, 3.0, 4.0);
-    //TODO: Can I avoid clone here?
-    s.set_transform(t.clone());
+    s.set_transform(t);
     assert_eq!(t, s.transform);
   }
   #[test]
   fn intersecting_a_scaled_sphere_with_a_ray() {
-    let origin = Tuple::point(0.0, 0.0, -5.0);
-    let direction = Tuple::vector(0.0, 0.0, 1.0);
+    let origin = Point3::new(0.0, 0.0, -5.0);
+    let direction = Vec3::new(0.0, 0.0, 1.0);
     let r = Ray::new(origin, direction);
     let mut s = Sphere::default();
     s.set_transform(scaling(2.0, 2.0, 2.0));
@@ -150,9 +173,19 @@ mod tests {
     assert_eq!(7.0, xs[1].t);
   }
   #[test]
+  fn replacing_the_transform_field_updates_the_inverse() {
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+    let mut s = Sphere::default();
+    s.transform = scaling(2.0, 2.0, 2.0).into();
+    assert_eq!(&scaling(0.5, 0.5, 0.5), s.transform().inverse());
+    let xs = s.intersect(r);
+    assert_eq!(3.0, xs[0].t);
+    assert_eq!(7.0, xs[1].t);
+  }
+  #[test]
   fn intersecting_a_translated_sphere_with_a_ray() {
-    let origin = Tuple::point(0.0, 0.0, -5.0);
-    let direction = Tuple::vector(0.0, 0.0, 1.0);
+    let origin = Point3::new(0.0, 0.0, -5.0);
+    let direction = Vec3::new(0.0, 0.0, 1.0);
     let r = Ray::new(origin, direction);
     let mut s = Sphere::default();
     s.set_transform(translation(5.0, 0.0, 0.0));
@@ -162,39 +195,39 @@ mod tests {
   #[test]
   fn the_normal_on_a_sphere_at_a_point_on_the_x_axis() {
     let s = Sphere::default();
-    let p = Tuple::point(1.0, 0.0, 0.0);
+    let p = Point3::new(1.0, 0.0, 0.0);
     let n = s.normal_at(p);
-    let expected = Tuple::vector(1.0, 0.0, 0.0);
+    let expected = Vec3::new(1.0, 0.0, 0.0);
     assert_eq!(expected, n);
   }
   #[test]
   fn the_normal_on_a_sphere_at_a_point_on_the_y_axis() {
     let s = Sphere::default();
-    let p = Tuple::point(0.0, 1.0, 0.0);
+    let p = Point3::new(0.0, 1.0, 0.0);
     let n = s.normal_at(p);
-    let expected = Tuple::vector(0.0, 1.0, 0.0);
+    let expected = Vec3::new(0.0, 1.0, 0.0);
     assert_eq!(expected, n);
   }
   #[test]
   fn the_normal_on_a_sphere_at_a_point_on_the_z_axis() {
     let s = Sphere::default();
-    let p = Tuple::point(0.0, 0.0, 1.0);
+    let p = Point3::new(0.0, 0.0, 1.0);
     let n = s.normal_at(p);
-    let expected = Tuple::vector(0.0, 0.0, 1.0);
+    let expected = Vec3::new(0.0, 0.0, 1.0);
     assert_eq!(expected, n);
   }
   #[test]
   fn the_normal_on_a_sphere_at_a_nonazial_point() {
     let s = Sphere::default();
-    let p = Tuple::point(3_f64.sqrt() / 3.0, 3_f64.sqrt() / 3.0, 3_f64.sqrt() / 3.0);
+    let p = Point3::new(3_f64.sqrt() / 3.0, 3_f64.sqrt() / 3.0, 3_f64.sqrt() / 3.0);
     let n = s.normal_at(p);
-    let expected = Tuple::vector(3_f64.sqrt() / 3.0, 3_f64.sqrt() / 3.0, 3_f64.sqrt() / 3.0);
+    let expected = Vec3::new(3_f64.sqrt() / 3.0, 3_f64.sqrt() / 3.0, 3_f64.sqrt() / 3.0);
     assert_eq!(expected, n);
   }
   #[test]
   fn the_normal_is_a_normalized_vector() {
     let s = Sphere::default();
-    let p = Tuple::point(3_f64.sqrt() / 3.0, 3_f64.sqrt() / 3.0, 3_f64.sqrt() / 3.0);
+    let p = Point3::new(3_f64.sqrt() / 3.0, 3_f64.sqrt() / 3.0, 3_f64.sqrt() / 3.0);
     let n = s.normal_at(p);
     let expected = n.normalize();
     assert_eq!(expected, n);
@@ -204,9 +237,9 @@ mod tests {
     let mut s = Sphere::default();
     s.set_transform(translation(0.0, 1.0, 0.0));
 
-    let p = Tuple::point(0.0, 1.70711, -0.70711);
+    let p = Point3::new(0.0, 1.70711, -0.70711);
     let n = s.normal_at(p);
-    let expected = Tuple::vector(0.0, 0.70711, -0.70711);
+    let expected = Vec3::new(0.0, 0.70711, -0.70711);
     assert_eq!(expected, n);
   }
   #[test]
@@ -215,9 +248,9 @@ mod tests {
     let m = scaling(1.0, 0.5, 1.0) * rotation_z(std::f64::consts::PI / 5.0);
     s.set_transform(m);
 
-    let p = Tuple::point(0.0, 2_f64.sqrt() / 2.0, -(2_f64.sqrt()) / 2.0);
+    let p = Point3::new(0.0, 2_f64.sqrt() / 2.0, -(2_f64.sqrt()) / 2.0);
     let n = s.normal_at(p);
-    let expected = Tuple::vector(0.0, 0.97014, -0.24254);
+    let expected = Vec3::new(0.0, 0.97014, -0.24254);
     assert_eq!(expected, n);
   }
   #[test]
@@ -230,9 +263,16 @@ mod tests {
   #[test]
   fn a_sphere_may_be_assigned_a_material() {
     let mut s = Sphere::default();
-    let mut m = s.material;
+    let mut m = s.material.clone();
     m.ambient = 1.0;
-    s.set_material(m);
+    s.set_material(m.clone());
     assert_eq!(m, s.material);
   }
+  #[test]
+  fn a_helper_for_producing_a_sphere_with_a_glassy_material() {
+    let s = Sphere::glass();
+    assert_eq!(&Matrix4::identity(), s.transform());
+    assert_eq!(1.0, s.material.transparency);
+    assert_eq!(1.5, s.material.refractive_index);
+  }
 }
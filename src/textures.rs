@@ -0,0 +1,537 @@
+use crate::canvas::Canvas;
+use crate::matrix::Matrix4;
+use crate::patterns::Pattern;
+use crate::transformations::Transform;
+use crate::tuple::{Color, Point3};
+use image::{DynamicImage, ImageError};
+use std::f64::consts::PI;
+use std::fmt::Debug;
+
+// A color source on a flat 2d surface, u and v both go from 0 to 1.
+// It is wrapped onto a shape by a TextureMapPattern or a CubeMapPattern.
+pub trait UvPattern: Debug + Send + Sync {
+    fn uv_pattern_at(&self, u: f64, v: f64) -> Color;
+}
+
+// Checkers with width squares along u and height squares along v
+#[derive(Debug, Clone, PartialEq)]
+pub struct UvCheckers {
+    pub width: f64,
+    pub height: f64,
+    pub a: Color,
+    pub b: Color,
+}
+
+impl UvCheckers {
+    pub fn new(width: f64, height: f64, a: Color, b: Color) -> UvCheckers {
+        UvCheckers {
+            width,
+            height,
+            a,
+            b,
+        }
+    }
+}
+
+impl UvPattern for UvCheckers {
+    fn uv_pattern_at(&self, u: f64, v: f64) -> Color {
+        let u2 = (u * self.width).floor() as i64;
+        let v2 = (v * self.height).floor() as i64;
+        if (u2 + v2) % 2 == 0 {
+            self.a
+        } else {
+            self.b
+        }
+    }
+}
+
+// A main color with a different color in each corner, handy to check
+// that the faces of a cube map are oriented correctly
+#[derive(Debug, Clone, PartialEq)]
+pub struct UvAlignCheck {
+    pub main: Color,
+    pub ul: Color,
+    pub ur: Color,
+    pub bl: Color,
+    pub br: Color,
+}
+
+impl UvAlignCheck {
+    pub fn new(main: Color, ul: Color, ur: Color, bl: Color, br: Color) -> UvAlignCheck {
+        UvAlignCheck {
+            main,
+            ul,
+            ur,
+            bl,
+            br,
+        }
+    }
+}
+
+impl UvPattern for UvAlignCheck {
+    fn uv_pattern_at(&self, u: f64, v: f64) -> Color {
+        if v > 0.8 {
+            if u < 0.2 {
+                return self.ul;
+            }
+            if u > 0.8 {
+                return self.ur;
+            }
+        } else if v < 0.2 {
+            if u < 0.2 {
+                return self.bl;
+            }
+            if u > 0.8 {
+                return self.br;
+            }
+        }
+        self.main
+    }
+}
+
+// An image used as a texture, v = 0 is the bottom row of the image
+#[derive(Debug)]
+pub struct UvImage {
+    pub canvas: Canvas,
+}
+
+impl UvImage {
+    pub fn new(canvas: Canvas) -> UvImage {
+        UvImage { canvas }
+    }
+
+    // Any format supported by the image crate, e.g. PPM or PNG
+    pub fn load(filename: &str) -> Result<UvImage, ImageError> {
+        UvImage::from_image(image::open(filename)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<UvImage, ImageError> {
+        UvImage::from_image(image::load_from_memory(bytes)?)
+    }
+
+    // An empty image has no pixel to sample, so it is refused here
+    fn from_image(image: DynamicImage) -> Result<UvImage, ImageError> {
+        let image = image.to_rgb();
+        let (width, height) = image.dimensions();
+        if width == 0 || height == 0 {
+            return Err(ImageError::DimensionError);
+        }
+        let mut canvas = Canvas::new(width as usize, height as usize);
+        for (x, y, pixel) in image.enumerate_pixels() {
+            let [r, g, b] = pixel.0;
+            let color = Color::new(
+                f64::from(r) / 255.0,
+                f64::from(g) / 255.0,
+                f64::from(b) / 255.0,
+            );
+            canvas.write_pixel(x as usize, y as usize, color);
+        }
+        Ok(UvImage::new(canvas))
+    }
+}
+
+impl UvPattern for UvImage {
+    fn uv_pattern_at(&self, u: f64, v: f64) -> Color {
+        // Mappings can land a hair outside [0, 1], which would index past
+        // the edge. Flip v so that up in the image is up on the shape.
+        let u = u.clamp(0.0, 1.0);
+        let v = 1.0 - v.clamp(0.0, 1.0);
+        let x = u * (self.canvas.width - 1) as f64;
+        let y = v * (self.canvas.height - 1) as f64;
+        self.canvas.pixel_at(x.round() as usize, y.round() as usize)
+    }
+}
+
+// How a point on a shape is converted to u and v
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UvMapping {
+    Spherical,
+    Planar,
+    Cylindrical,
+}
+
+impl UvMapping {
+    pub fn map(self, point: Point3) -> (f64, f64) {
+        match self {
+            UvMapping::Spherical => spherical_map(point),
+            UvMapping::Planar => planar_map(point),
+            UvMapping::Cylindrical => cylindrical_map(point),
+        }
+    }
+}
+
+// Longitude and latitude of the point on a unit sphere
+pub fn spherical_map(point: Point3) -> (f64, f64) {
+    // The azimuthal angle, -pi < theta <= pi, increasing counter clockwise seen from above
+    let theta = point.0.atan2(point.2);
+    let radius = (point - Point3::origin()).magnitude();
+    // The polar angle, 0 <= phi <= pi
+    let phi = (point.1 / radius).acos();
+    let raw_u = theta / (2.0 * PI);
+    // Flip u so it increases clockwise seen from above
+    let u = 1.0 - (raw_u + 0.5);
+    // Flip v so it is 0 at the south pole
+    let v = 1.0 - phi / PI;
+    (u, v)
+}
+
+// Repeats every unit along x and z
+pub fn planar_map(point: Point3) -> (f64, f64) {
+    (point.0.rem_euclid(1.0), point.2.rem_euclid(1.0))
+}
+
+// Around the y axis like the spherical map, repeating every unit along y
+pub fn cylindrical_map(point: Point3) -> (f64, f64) {
+    let theta = point.0.atan2(point.2);
+    let raw_u = theta / (2.0 * PI);
+    let u = 1.0 - (raw_u + 0.5);
+    (u, point.1.rem_euclid(1.0))
+}
+
+// Wraps a 2d pattern onto a shape using one of the mappings
+#[derive(Debug)]
+pub struct TextureMapPattern {
+    pub uv_pattern: Box<dyn UvPattern>,
+    pub mapping: UvMapping,
+    pub transform: Transform,
+}
+
+impl TextureMapPattern {
+    pub fn new(uv_pattern: Box<dyn UvPattern>, mapping: UvMapping) -> TextureMapPattern {
+        TextureMapPattern {
+            uv_pattern,
+            mapping,
+            transform: Transform::identity(),
+        }
+    }
+}
+
+impl Pattern for TextureMapPattern {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn set_transform(&mut self, t: Matrix4) {
+        self.transform = Transform::new(t);
+    }
+
+    fn pattern_at(&self, point: Point3) -> Color {
+        let (u, v) = self.mapping.map(point);
+        self.uv_pattern.uv_pattern_at(u, v)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CubeFace {
+    Left,
+    Right,
+    Front,
+    Back,
+    Up,
+    Down,
+}
+
+// The face of a cube from -1 to 1 the point is on, by its largest component
+pub fn face_from_point(point: Point3) -> CubeFace {
+    let coord = point.0.abs().max(point.1.abs()).max(point.2.abs());
+    if coord == point.0 {
+        CubeFace::Right
+    } else if coord == -point.0 {
+        CubeFace::Left
+    } else if coord == point.1 {
+        CubeFace::Up
+    } else if coord == -point.1 {
+        CubeFace::Down
+    } else if coord == point.2 {
+        CubeFace::Front
+    } else {
+        CubeFace::Back
+    }
+}
+
+// u and v on one face of the cube, each face is seen from outside the cube
+pub fn cube_uv(face: CubeFace, point: Point3) -> (f64, f64) {
+    let Point3(x, y, z) = point;
+    let (u, v) = match face {
+        CubeFace::Front => (x + 1.0, y + 1.0),
+        CubeFace::Back => (1.0 - x, y + 1.0),
+        CubeFace::Left => (z + 1.0, y + 1.0),
+        CubeFace::Right => (1.0 - z, y + 1.0),
+        CubeFace::Up => (x + 1.0, 1.0 - z),
+        CubeFace::Down => (x + 1.0, z + 1.0),
+    };
+    (u.rem_euclid(2.0) / 2.0, v.rem_euclid(2.0) / 2.0)
+}
+
+// A separate 2d pattern for each face of a cube, e.g. a labelled box
+#[derive(Debug)]
+pub struct CubeMapPattern {
+    pub left: Box<dyn UvPattern>,
+    pub front: Box<dyn UvPattern>,
+    pub right: Box<dyn UvPattern>,
+    pub back: Box<dyn UvPattern>,
+    pub up: Box<dyn UvPattern>,
+    pub down: Box<dyn UvPattern>,
+    pub transform: Transform,
+}
+
+impl CubeMapPattern {
+    pub fn new(
+        left: Box<dyn UvPattern>,
+        front: Box<dyn UvPattern>,
+        right: Box<dyn UvPattern>,
+        back: Box<dyn UvPattern>,
+        up: Box<dyn UvPattern>,
+        down: Box<dyn UvPattern>,
+    ) -> CubeMapPattern {
+        CubeMapPattern {
+            left,
+            front,
+            right,
+            back,
+            up,
+            down,
+            transform: Transform::identity(),
+        }
+    }
+}
+
+impl Pattern for CubeMapPattern {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn set_transform(&mut self, t: Matrix4) {
+        self.transform = Transform::new(t);
+    }
+
+    fn pattern_at(&self, point: Point3) -> Color {
+        let face = face_from_point(point);
+        let (u, v) = cube_uv(face, point);
+        let uv_pattern = match face {
+            CubeFace::Left => &self.left,
+            CubeFace::Right => &self.right,
+            CubeFace::Front => &self.front,
+            CubeFace::Back => &self.back,
+            CubeFace::Up => &self.up,
+            CubeFace::Down => &self.down,
+        };
+        uv_pattern.uv_pattern_at(u, v)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+
+mod tests {
+    use super::*;
+
+    fn white() -> Color {
+        Color::new(1.0, 1.0, 1.0)
+    }
+
+    fn black() -> Color {
+        Color::new(0.0, 0.0, 0.0)
+    }
+
+    fn assert_uv(expected: (f64, f64), actual: (f64, f64)) {
+        assert!((expected.0 - actual.0).abs() < 1e-5, "u {:?}", actual);
+        assert!((expected.1 - actual.1).abs() < 1e-5, "v {:?}", actual);
+    }
+
+    #[test]
+    fn checker_pattern_in_2d() {
+        let checkers = UvCheckers::new(2.0, 2.0, black(), white());
+        assert_eq!(black(), checkers.uv_pattern_at(0.0, 0.0));
+        assert_eq!(white(), checkers.uv_pattern_at(0.5, 0.0));
+        assert_eq!(white(), checkers.uv_pattern_at(0.0, 0.5));
+        assert_eq!(black(), checkers.uv_pattern_at(0.5, 0.5));
+        assert_eq!(black(), checkers.uv_pattern_at(1.0, 1.0));
+    }
+    #[test]
+    fn using_a_spherical_mapping_on_a_3d_point() {
+        let s2 = 2_f64.sqrt() / 2.0;
+        assert_uv((0.0, 0.5), spherical_map(Point3::new(0.0, 0.0, -1.0)));
+        assert_uv((0.25, 0.5), spherical_map(Point3::new(1.0, 0.0, 0.0)));
+        assert_uv((0.5, 0.5), spherical_map(Point3::new(0.0, 0.0, 1.0)));
+        assert_uv((0.75, 0.5), spherical_map(Point3::new(-1.0, 0.0, 0.0)));
+        assert_uv((0.5, 1.0), spherical_map(Point3::new(0.0, 1.0, 0.0)));
+        assert_uv((0.5, 0.0), spherical_map(Point3::new(0.0, -1.0, 0.0)));
+        assert_uv((0.25, 0.75), spherical_map(Point3::new(s2, s2, 0.0)));
+    }
+    #[test]
+    fn using_a_texture_map_pattern_with_a_spherical_map() {
+        let checkers = UvCheckers::new(16.0, 8.0, black(), white());
+        let pattern = TextureMapPattern::new(Box::new(checkers), UvMapping::Spherical);
+        let points = [
+            (Point3::new(0.4315, 0.4670, 0.7719), white()),
+            (Point3::new(-0.9654, 0.2552, -0.0534), black()),
+            (Point3::new(0.1039, 0.7090, 0.6975), white()),
+            (Point3::new(-0.4986, -0.7856, -0.3663), black()),
+            (Point3::new(-0.0317, -0.9395, 0.3411), black()),
+            (Point3::new(0.4809, -0.7721, 0.4154), black()),
+            (Point3::new(0.0285, -0.9612, -0.2745), black()),
+            (Point3::new(-0.5734, -0.2162, -0.7903), white()),
+            (Point3::new(0.7688, -0.1470, 0.6223), black()),
+            (Point3::new(-0.7652, 0.2175, 0.6060), black()),
+        ];
+        for &(point, color) in points.iter() {
+            assert_eq!(color, pattern.pattern_at(point));
+        }
+    }
+    #[test]
+    fn using_a_planar_mapping_on_a_3d_point() {
+        assert_uv((0.25, 0.5), planar_map(Point3::new(0.25, 0.0, 0.5)));
+        assert_uv((0.25, 0.5), planar_map(Point3::new(0.25, 0.0, -0.5)));
+        assert_uv((0.25, 0.5), planar_map(Point3::new(0.25, 0.5, -1.5)));
+        assert_uv((0.25, 0.5), planar_map(Point3::new(1.25, 0.0, 0.5)));
+        assert_uv((0.25, 0.25), planar_map(Point3::new(0.25, 1.0, -1.75)));
+        assert_uv((0.0, 0.0), planar_map(Point3::new(1.0, 0.0, -1.0)));
+        assert_uv((0.0, 0.0), planar_map(Point3::new(0.0, 0.0, 0.0)));
+    }
+    #[test]
+    fn using_a_cylindrical_mapping_on_a_3d_point() {
+        let s2 = 2_f64.sqrt() / 2.0;
+        assert_uv((0.0, 0.0), cylindrical_map(Point3::new(0.0, 0.0, -1.0)));
+        assert_uv((0.0, 0.5), cylindrical_map(Point3::new(0.0, 0.5, -1.0)));
+        assert_uv((0.0, 0.0), cylindrical_map(Point3::new(0.0, 1.0, -1.0)));
+        assert_uv((0.125, 0.25), cylindrical_map(Point3::new(s2, 0.25, -s2)));
+        assert_uv((0.25, 0.5), cylindrical_map(Point3::new(1.0, 0.5, 0.0)));
+        assert_uv((0.375, 0.75), cylindrical_map(Point3::new(s2, 0.75, s2)));
+        assert_uv((0.625, 0.25), cylindrical_map(Point3::new(-s2, 1.25, s2)));
+        assert_uv((0.875, 0.75), cylindrical_map(Point3::new(-s2, 0.75, -s2)));
+    }
+    #[test]
+    fn layout_of_the_align_check_pattern() {
+        let main = Color::new(1.0, 1.0, 1.0);
+        let ul = Color::new(1.0, 0.0, 0.0);
+        let ur = Color::new(1.0, 1.0, 0.0);
+        let bl = Color::new(0.0, 1.0, 0.0);
+        let br = Color::new(0.0, 1.0, 1.0);
+        let pattern = UvAlignCheck::new(main, ul, ur, bl, br);
+        assert_eq!(main, pattern.uv_pattern_at(0.5, 0.5));
+        assert_eq!(ul, pattern.uv_pattern_at(0.1, 0.9));
+        assert_eq!(ur, pattern.uv_pattern_at(0.9, 0.9));
+        assert_eq!(bl, pattern.uv_pattern_at(0.1, 0.1));
+        assert_eq!(br, pattern.uv_pattern_at(0.9, 0.1));
+    }
+    #[test]
+    fn identifying_the_face_of_a_cube_from_a_point() {
+        assert_eq!(
+            CubeFace::Left,
+            face_from_point(Point3::new(-1.0, 0.5, -0.25))
+        );
+        assert_eq!(
+            CubeFace::Right,
+            face_from_point(Point3::new(1.1, -0.75, 0.8))
+        );
+        assert_eq!(CubeFace::Front, face_from_point(Point3::new(0.1, 0.6, 0.9)));
+        assert_eq!(
+            CubeFace::Back,
+            face_from_point(Point3::new(-0.7, 0.0, -2.0))
+        );
+        assert_eq!(CubeFace::Up, face_from_point(Point3::new(0.5, 1.0, 0.9)));
+        assert_eq!(
+            CubeFace::Down,
+            face_from_point(Point3::new(-0.2, -1.3, 1.1))
+        );
+    }
+    #[test]
+    fn uv_mapping_the_faces_of_a_cube() {
+        let front = CubeFace::Front;
+        assert_uv((0.25, 0.75), cube_uv(front, Point3::new(-0.5, 0.5, 1.0)));
+        assert_uv((0.75, 0.25), cube_uv(front, Point3::new(0.5, -0.5, 1.0)));
+        let back = CubeFace::Back;
+        assert_uv((0.25, 0.75), cube_uv(back, Point3::new(0.5, 0.5, -1.0)));
+        assert_uv((0.75, 0.25), cube_uv(back, Point3::new(-0.5, -0.5, -1.0)));
+        let left = CubeFace::Left;
+        assert_uv((0.25, 0.75), cube_uv(left, Point3::new(-1.0, 0.5, -0.5)));
+        assert_uv((0.75, 0.25), cube_uv(left, Point3::new(-1.0, -0.5, 0.5)));
+        let right = CubeFace::Right;
+        assert_uv((0.25, 0.75), cube_uv(right, Point3::new(1.0, 0.5, 0.5)));
+        assert_uv((0.75, 0.25), cube_uv(right, Point3::new(1.0, -0.5, -0.5)));
+        let up = CubeFace::Up;
+        assert_uv((0.25, 0.75), cube_uv(up, Point3::new(-0.5, 1.0, -0.5)));
+        assert_uv((0.75, 0.25), cube_uv(up, Point3::new(0.5, 1.0, 0.5)));
+        let down = CubeFace::Down;
+        assert_uv((0.25, 0.75), cube_uv(down, Point3::new(-0.5, -1.0, 0.5)));
+        assert_uv((0.75, 0.25), cube_uv(down, Point3::new(0.5, -1.0, -0.5)));
+    }
+    #[test]
+    fn finding_the_colors_on_a_mapped_cube() {
+        let red = Color::new(1.0, 0.0, 0.0);
+        let yellow = Color::new(1.0, 1.0, 0.0);
+        let brown = Color::new(1.0, 0.5, 0.0);
+        let green = Color::new(0.0, 1.0, 0.0);
+        let cyan = Color::new(0.0, 1.0, 1.0);
+        let blue = Color::new(0.0, 0.0, 1.0);
+        let purple = Color::new(1.0, 0.0, 1.0);
+        let white = white();
+        let pattern = CubeMapPattern::new(
+            Box::new(UvAlignCheck::new(yellow, cyan, red, blue, brown)),
+            Box::new(UvAlignCheck::new(cyan, red, yellow, brown, green)),
+            Box::new(UvAlignCheck::new(red, yellow, purple, green, white)),
+            Box::new(UvAlignCheck::new(green, purple, cyan, white, blue)),
+            Box::new(UvAlignCheck::new(brown, cyan, purple, red, yellow)),
+            Box::new(UvAlignCheck::new(purple, brown, green, blue, white)),
+        );
+        // left face
+        assert_eq!(yellow, pattern.pattern_at(Point3::new(-1.0, 0.0, 0.0)));
+        assert_eq!(cyan, pattern.pattern_at(Point3::new(-1.0, 0.9, -0.9)));
+        assert_eq!(red, pattern.pattern_at(Point3::new(-1.0, 0.9, 0.9)));
+        assert_eq!(blue, pattern.pattern_at(Point3::new(-1.0, -0.9, -0.9)));
+        assert_eq!(brown, pattern.pattern_at(Point3::new(-1.0, -0.9, 0.9)));
+        // front face
+        assert_eq!(cyan, pattern.pattern_at(Point3::new(0.0, 0.0, 1.0)));
+        assert_eq!(red, pattern.pattern_at(Point3::new(-0.9, 0.9, 1.0)));
+        assert_eq!(green, pattern.pattern_at(Point3::new(0.9, -0.9, 1.0)));
+        // up face
+        assert_eq!(brown, pattern.pattern_at(Point3::new(0.0, 1.0, 0.0)));
+        assert_eq!(cyan, pattern.pattern_at(Point3::new(-0.9, 1.0, -0.9)));
+        assert_eq!(yellow, pattern.pattern_at(Point3::new(0.9, 1.0, 0.9)));
+        // down face
+        assert_eq!(purple, pattern.pattern_at(Point3::new(0.0, -1.0, 0.0)));
+        assert_eq!(brown, pattern.pattern_at(Point3::new(-0.9, -1.0, 0.9)));
+        assert_eq!(white, pattern.pattern_at(Point3::new(0.9, -1.0, -0.9)));
+    }
+    #[test]
+    fn loading_an_image_from_a_ppm() {
+        // A 10x10 image where the gray value of pixel x, y is ((x + y) % 10) * 25
+        let mut ppm = String::from("P3\n10 10\n255\n");
+        for y in 0..10 {
+            for x in 0..10 {
+                let c = ((x + y) % 10) * 25;
+                ppm.push_str(&format!("{} {} {} ", c, c, c));
+            }
+            ppm.push('\n');
+        }
+        let image = UvImage::from_bytes(ppm.as_bytes()).unwrap();
+        assert_eq!(10, image.canvas.width);
+        assert_eq!(10, image.canvas.height);
+        let gray = |c: f64| Color::new(c / 255.0, c / 255.0, c / 255.0);
+        assert_eq!(gray(225.0), image.uv_pattern_at(0.0, 0.0));
+        assert_eq!(gray(50.0), image.uv_pattern_at(0.3, 0.0));
+        assert_eq!(gray(25.0), image.uv_pattern_at(0.6, 0.3));
+        assert_eq!(gray(225.0), image.uv_pattern_at(1.0, 1.0));
+    }
+    #[test]
+    fn u_and_v_outside_the_unit_square_use_the_edge_of_an_image() {
+        let mut canvas = Canvas::new(2, 2);
+        let red = Color::new(1.0, 0.0, 0.0);
+        let blue = Color::new(0.0, 0.0, 1.0);
+        canvas.write_pixel(1, 0, red);
+        canvas.write_pixel(0, 1, blue);
+        let image = UvImage::new(canvas);
+        assert_eq!(red, image.uv_pattern_at(1.5, 1.0001));
+        assert_eq!(blue, image.uv_pattern_at(-0.0001, -2.0));
+    }
+    #[test]
+    fn loading_an_invalid_image_fails() {
+        assert!(UvImage::from_bytes(b"not an image").is_err());
+        assert!(UvImage::load("/nonexistent/texture.png").is_err());
+    }
+    #[test]
+    fn loading_an_empty_image_fails() {
+        assert!(UvImage::from_bytes(b"P3\n0 0\n255\n").is_err());
+    }
+}
@@ -1,37 +1,38 @@
-use crate::matrix::Matrix;
+use crate::matrix::{Matrix4, SingularMatrix};
+use crate::tuple::{Point3, Vec3};
 
-pub fn translation(x: f64, y: f64, z: f64) -> Matrix {
-  let t = Matrix::identity();
+pub fn translation(x: f64, y: f64, z: f64) -> Matrix4 {
+  let t = Matrix4::identity();
   t.update_at(0, 3, x).update_at(1, 3, y).update_at(2, 3, z)
 }
-pub fn scaling(x: f64, y: f64, z: f64) -> Matrix {
-  let t = Matrix::identity();
+pub fn scaling(x: f64, y: f64, z: f64) -> Matrix4 {
+  let t = Matrix4::identity();
   t.update_at(0, 0, x).update_at(1, 1, y).update_at(2, 2, z)
 }
-pub fn rotation_x(r: f64) -> Matrix {
-  let t = Matrix::identity();
+pub fn rotation_x(r: f64) -> Matrix4 {
+  let t = Matrix4::identity();
   t.update_at(1, 1, r.cos())
     .update_at(1, 2, -r.sin())
     .update_at(2, 1, r.sin())
     .update_at(2, 2, r.cos())
 }
-pub fn rotation_y(r: f64) -> Matrix {
-  let t = Matrix::identity();
+pub fn rotation_y(r: f64) -> Matrix4 {
+  let t = Matrix4::identity();
   t.update_at(0, 0, r.cos())
     .update_at(0, 2, r.sin())
     .update_at(2, 0, -r.sin())
     .update_at(2, 2, r.cos())
 }
-pub fn rotation_z(r: f64) -> Matrix {
-  let t = Matrix::identity();
+pub fn rotation_z(r: f64) -> Matrix4 {
+  let t = Matrix4::identity();
   t.update_at(0, 0, r.cos())
     .update_at(0, 1, -r.sin())
     .update_at(1, 0, r.sin())
     .update_at(1, 1, r.cos())
 }
 
-pub fn shearing(xy: f64, xz: f64, yx: f64, yz: f64, zx: f64, zy: f64) -> Matrix {
-  let t = Matrix::identity();
+pub fn shearing(xy: f64, xz: f64, yx: f64, yz: f64, zx: f64, zy: f64) -> Matrix4 {
+  let t = Matrix4::identity();
   t.update_at(0, 1, xy)
     .update_at(0, 2, xz)
     .update_at(1, 0, yx)
@@ -40,168 +41,311 @@ pub fn shearing(xy: f64, xz: f64, yx: f64, yz: f64, zx: f64, zy: f64) -> Matrix
     .update_at(2, 1, zy)
 }
 
+// Orients the world relative to the eye - from is the eye position, to is the
+// point looked at and up is roughly the upward direction
+pub fn view_transform(from: Point3, to: Point3, up: Vec3) -> Matrix4 {
+  let forward = (to - from).normalize();
+  let left = forward.cross(up.normalize());
+  let true_up = left.cross(forward);
+  let orientation = Matrix4::new([
+    [left.0, left.1, left.2, 0.0],
+    [true_up.0, true_up.1, true_up.2, 0.0],
+    [-forward.0, -forward.1, -forward.2, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+  ]);
+  orientation * translation(-from.0, -from.1, -from.2)
+}
+
+// A transform together with its inverse and inverse transpose. They are
+// computed once here instead of for every ray and every normal. A singular
+// transform, like a scaling by zero, is kept but has no inverse.
+#[derive(Debug, Clone, Copy)]
+pub struct Transform {
+  matrix: Matrix4,
+  inverse: Option<Matrix4>,
+  inverse_transpose: Option<Matrix4>,
+}
+
+impl Transform {
+  pub fn new(matrix: Matrix4) -> Transform {
+    let inverse = matrix.try_inverse().ok();
+    Transform {
+      matrix,
+      inverse,
+      inverse_transpose: inverse.map(|i| i.transpose()),
+    }
+  }
+
+  pub fn identity() -> Transform {
+    Transform::new(Matrix4::identity())
+  }
+
+  pub fn matrix(&self) -> &Matrix4 {
+    &self.matrix
+  }
+
+  pub fn is_singular(&self) -> bool {
+    self.inverse.is_none()
+  }
+
+  pub fn try_inverse(&self) -> Result<&Matrix4, SingularMatrix> {
+    self.inverse.as_ref().ok_or(SingularMatrix)
+  }
+
+  // Only for transforms already known to be invertible: a shape that was hit,
+  // which its intersect checked, or a camera, which set_transform checked
+  pub(crate) fn inverse(&self) -> &Matrix4 {
+    self.try_inverse().expect("Transform is singular")
+  }
+
+  pub(crate) fn inverse_transpose(&self) -> &Matrix4 {
+    self
+      .inverse_transpose
+      .as_ref()
+      .expect("Transform is singular")
+  }
+}
+
+impl From<Matrix4> for Transform {
+  fn from(matrix: Matrix4) -> Transform {
+    Transform::new(matrix)
+  }
+}
+
+// The cached matrices follow from the matrix, so only it is compared
+impl PartialEq for Transform {
+  fn eq(&self, other: &Transform) -> bool {
+    self.matrix == other.matrix
+  }
+}
+
+impl PartialEq<Matrix4> for Transform {
+  fn eq(&self, other: &Matrix4) -> bool {
+    self.matrix == *other
+  }
+}
+
+impl PartialEq<Transform> for Matrix4 {
+  fn eq(&self, other: &Transform) -> bool {
+    *self == other.matrix
+  }
+}
+
 #[cfg(test)]
 
 mod tests {
   use super::*;
-  use crate::tuple::Tuple;
 
   #[test]
   fn multiplying_by_a_translation_matrix() {
     let transform = translation(5.0, -3.0, 2.0);
-    let p = Tuple::point(-3.0, 4.0, 5.0);
-    let expected = Tuple::point(2.0, 1.0, 7.0);
+    let p = Point3::new(-3.0, 4.0, 5.0);
+    let expected = Point3::new(2.0, 1.0, 7.0);
     assert_eq!(expected, transform * p);
   }
   #[test]
   fn multiplying_by_the_inverse_of_a_translation() {
     let transform = translation(5.0, -3.0, 2.0);
-    let inv = transform.inverse();
-    let p = Tuple::point(-3.0, 4.0, 5.0);
-    let expected = Tuple::point(-8.0, 7.0, 3.0);
+    let inv = transform.try_inverse().unwrap();
+    let p = Point3::new(-3.0, 4.0, 5.0);
+    let expected = Point3::new(-8.0, 7.0, 3.0);
     assert_eq!(expected, inv * p);
   }
   #[test]
   fn translation_does_not_affect_vectors() {
     let transform = translation(5.0, -3.0, 2.0);
-    let v = Tuple::vector(-3.0, 4.0, 5.0);
+    let v = Vec3::new(-3.0, 4.0, 5.0);
     let expected = v;
     assert_eq!(expected, transform * v);
   }
   #[test]
   fn a_scaling_matrix_applied_to_a_point() {
     let transform = scaling(2.0, 3.0, 4.0);
-    let p = Tuple::point(-4.0, 6.0, 8.0);
-    let expected = Tuple::point(-8.0, 18.0, 32.0);
+    let p = Point3::new(-4.0, 6.0, 8.0);
+    let expected = Point3::new(-8.0, 18.0, 32.0);
     assert_eq!(expected, transform * p);
   }
   #[test]
   fn a_scaling_matrix_applied_to_a_vector() {
     let transform = scaling(2.0, 3.0, 4.0);
-    let v = Tuple::vector(-4.0, 6.0, 8.0);
-    let expected = Tuple::vector(-8.0, 18.0, 32.0);
+    let v = Vec3::new(-4.0, 6.0, 8.0);
+    let expected = Vec3::new(-8.0, 18.0, 32.0);
     assert_eq!(expected, transform * v);
   }
   #[test]
   fn multiplying_by_the_inverse_of_a_scaling() {
     let transform = scaling(2.0, 3.0, 4.0);
-    let inv = transform.inverse();
-    let v = Tuple::vector(-4.0, 6.0, 8.0);
-    let expected = Tuple::vector(-2.0, 2.0, 2.0);
+    let inv = transform.try_inverse().unwrap();
+    let v = Vec3::new(-4.0, 6.0, 8.0);
+    let expected = Vec3::new(-2.0, 2.0, 2.0);
     assert_eq!(expected, inv * v);
   }
   #[test]
   fn reflection_is_scaling_by_a_negative_value() {
     let transform = scaling(-1.0, 1.0, 1.0);
-    let p = Tuple::point(2.0, 3.0, 4.0);
-    let expected = Tuple::point(-2.0, 3.0, 4.0);
+    let p = Point3::new(2.0, 3.0, 4.0);
+    let expected = Point3::new(-2.0, 3.0, 4.0);
     assert_eq!(expected, transform * p);
   }
 
   #[test]
   fn rotating_a_point_around_the_x_axis() {
-    let p = Tuple::point(0.0, 1.0, 0.0);
+    let p = Point3::new(0.0, 1.0, 0.0);
     let half_quarter = rotation_x(std::f64::consts::PI / 4.0);
     let full_quarter = rotation_x(std::f64::consts::PI / 2.0);
-    let expected_half_quarter = Tuple::point(0.0, 2.0_f64.sqrt() / 2.0, 2.0_f64.sqrt() / 2.0);
-    let expected_full_quarter = Tuple::point(0.0, 0.0, 1.0);
+    let expected_half_quarter = Point3::new(0.0, 2.0_f64.sqrt() / 2.0, 2.0_f64.sqrt() / 2.0);
+    let expected_full_quarter = Point3::new(0.0, 0.0, 1.0);
     assert_eq!(expected_half_quarter, half_quarter * p);
     assert_eq!(expected_full_quarter, full_quarter * p);
   }
   #[test]
   fn the_inverse_of_an_x_rotation_rotates_in_the_opposite_direction() {
-    let p = Tuple::point(0.0, 1.0, 0.0);
-    let half_quarter_inv = rotation_x(std::f64::consts::PI / 4.0).inverse();
-    let expected_half_quarter = Tuple::point(0.0, 2.0_f64.sqrt() / 2.0, -(2.0_f64.sqrt()) / 2.0);
+    let p = Point3::new(0.0, 1.0, 0.0);
+    let half_quarter_inv = rotation_x(std::f64::consts::PI / 4.0)
+      .try_inverse()
+      .unwrap();
+    let expected_half_quarter = Point3::new(0.0, 2.0_f64.sqrt() / 2.0, -(2.0_f64.sqrt()) / 2.0);
     assert_eq!(expected_half_quarter, half_quarter_inv * p);
   }
   #[test]
   fn rotating_a_point_around_the_y_axis() {
-    let p = Tuple::point(0.0, 0.0, 1.0);
+    let p = Point3::new(0.0, 0.0, 1.0);
     let half_quarter = rotation_y(std::f64::consts::PI / 4.0);
     let full_quarter = rotation_y(std::f64::consts::PI / 2.0);
-    let expected_half_quarter = Tuple::point(2.0_f64.sqrt() / 2.0, 0.0, 2.0_f64.sqrt() / 2.0);
-    let expected_full_quarter = Tuple::point(1.0, 0.0, 0.0);
+    let expected_half_quarter = Point3::new(2.0_f64.sqrt() / 2.0, 0.0, 2.0_f64.sqrt() / 2.0);
+    let expected_full_quarter = Point3::new(1.0, 0.0, 0.0);
     assert_eq!(expected_half_quarter, half_quarter * p);
     assert_eq!(expected_full_quarter, full_quarter * p);
   }
   #[test]
   fn rotating_a_point_around_the_z_axis() {
-    let p = Tuple::point(0.0, 1.0, 0.0);
+    let p = Point3::new(0.0, 1.0, 0.0);
     let half_quarter = rotation_z(std::f64::consts::PI / 4.0);
     let full_quarter = rotation_z(std::f64::consts::PI / 2.0);
-    let expected_half_quarter = Tuple::point(-(2.0_f64.sqrt()) / 2.0, 2.0_f64.sqrt() / 2.0, 0.0);
-    let expected_full_quarter = Tuple::point(-1.0, 0.0, 0.0);
+    let expected_half_quarter = Point3::new(-(2.0_f64.sqrt()) / 2.0, 2.0_f64.sqrt() / 2.0, 0.0);
+    let expected_full_quarter = Point3::new(-1.0, 0.0, 0.0);
     assert_eq!(expected_half_quarter, half_quarter * p);
     assert_eq!(expected_full_quarter, full_quarter * p);
   }
   #[test]
   fn a_shearing_transformation_moves_x_in_propoption_to_y() {
     let transform = shearing(1.0, 0.0, 0.0, 0.0, 0.0, 0.0);
-    let p = Tuple::point(2.0, 3.0, 4.0);
-    let expected = Tuple::point(5.0, 3.0, 4.0);
+    let p = Point3::new(2.0, 3.0, 4.0);
+    let expected = Point3::new(5.0, 3.0, 4.0);
     assert_eq!(expected, transform * p);
   }
   #[test]
   fn a_shearing_transformation_moves_x_in_propoption_to_z() {
     let transform = shearing(0.0, 1.0, 0.0, 0.0, 0.0, 0.0);
-    let p = Tuple::point(2.0, 3.0, 4.0);
-    let expected = Tuple::point(6.0, 3.0, 4.0);
+    let p = Point3::new(2.0, 3.0, 4.0);
+    let expected = Point3::new(6.0, 3.0, 4.0);
     assert_eq!(expected, transform * p);
   }
   #[test]
   fn a_shearing_transformation_moves_y_in_propoption_to_x() {
     let transform = shearing(0.0, 0.0, 1.0, 0.0, 0.0, 0.0);
-    let p = Tuple::point(2.0, 3.0, 4.0);
-    let expected = Tuple::point(2.0, 5.0, 4.0);
+    let p = Point3::new(2.0, 3.0, 4.0);
+    let expected = Point3::new(2.0, 5.0, 4.0);
     assert_eq!(expected, transform * p);
   }
   #[test]
   fn a_shearing_transformation_moves_y_in_propoption_to_z() {
     let transform = shearing(0.0, 0.0, 0.0, 1.0, 0.0, 0.0);
-    let p = Tuple::point(2.0, 3.0, 4.0);
-    let expected = Tuple::point(2.0, 7.0, 4.0);
+    let p = Point3::new(2.0, 3.0, 4.0);
+    let expected = Point3::new(2.0, 7.0, 4.0);
     assert_eq!(expected, transform * p);
   }
   #[test]
   fn a_shearing_transformation_moves_z_in_propoption_to_x() {
     let transform = shearing(0.0, 0.0, 0.0, 0.0, 1.0, 0.0);
-    let p = Tuple::point(2.0, 3.0, 4.0);
-    let expected = Tuple::point(2.0, 3.0, 6.0);
+    let p = Point3::new(2.0, 3.0, 4.0);
+    let expected = Point3::new(2.0, 3.0, 6.0);
     assert_eq!(expected, transform * p);
   }
   #[test]
   fn a_shearing_transformation_moves_z_in_propoption_to_y() {
     let transform = shearing(0.0, 0.0, 0.0, 0.0, 0.0, 1.0);
-    let p = Tuple::point(2.0, 3.0, 4.0);
-    let expected = Tuple::point(2.0, 3.0, 7.0);
+    let p = Point3::new(2.0, 3.0, 4.0);
+    let expected = Point3::new(2.0, 3.0, 7.0);
     assert_eq!(expected, transform * p);
   }
   #[test]
   fn individual_transformations_are_applied_in_sequence() {
-    let p = Tuple::point(1.0, 0.0, 1.0);
+    let p = Point3::new(1.0, 0.0, 1.0);
     let a = rotation_x(std::f64::consts::PI / 2.0);
     let b = scaling(5.0, 5.0, 5.0);
     let c = translation(10.0, 5.0, 7.0);
     let p2 = a * p;
-    let p2exp = Tuple::point(1.0, -1.0, 0.0);
+    let p2exp = Point3::new(1.0, -1.0, 0.0);
     assert_eq!(p2exp, p2);
 
     let p3 = b * p2;
-    let p3exp = Tuple::point(5.0, -5.0, 0.0);
+    let p3exp = Point3::new(5.0, -5.0, 0.0);
     assert_eq!(p3exp, p3);
     let p4 = c * p3;
-    let p4exp = Tuple::point(15.0, 0.0, 7.0);
+    let p4exp = Point3::new(15.0, 0.0, 7.0);
     assert_eq!(p4exp, p4);
   }
   #[test]
   fn chained_transformations_must_be_applied_in_reverse_order() {
-    let p = Tuple::point(1.0, 0.0, 1.0);
+    let p = Point3::new(1.0, 0.0, 1.0);
     let a = rotation_x(std::f64::consts::PI / 2.0);
     let b = scaling(5.0, 5.0, 5.0);
     let c = translation(10.0, 5.0, 7.0);
     let t = c * a * b;
-    let texp = Tuple::point(15.0, 0.0, 7.0);
+    let texp = Point3::new(15.0, 0.0, 7.0);
     assert_eq!(texp, t * p);
   }
+  #[test]
+  fn the_transformation_matrix_for_the_default_orientation() {
+    let from = Point3::new(0.0, 0.0, 0.0);
+    let to = Point3::new(0.0, 0.0, -1.0);
+    let up = Vec3::new(0.0, 1.0, 0.0);
+    let t = view_transform(from, to, up);
+    assert_eq!(Matrix4::identity(), t);
+  }
+  #[test]
+  fn a_view_transformation_matrix_looking_in_positive_z_direction() {
+    let from = Point3::new(0.0, 0.0, 0.0);
+    let to = Point3::new(0.0, 0.0, 1.0);
+    let up = Vec3::new(0.0, 1.0, 0.0);
+    let t = view_transform(from, to, up);
+    assert_eq!(scaling(-1.0, 1.0, -1.0), t);
+  }
+  #[test]
+  fn the_view_transformation_moves_the_world() {
+    let from = Point3::new(0.0, 0.0, 8.0);
+    let to = Point3::new(0.0, 0.0, 0.0);
+    let up = Vec3::new(0.0, 1.0, 0.0);
+    let t = view_transform(from, to, up);
+    assert_eq!(translation(0.0, 0.0, -8.0), t);
+  }
+  #[test]
+  fn an_arbitrary_view_transformation() {
+    let from = Point3::new(1.0, 3.0, 2.0);
+    let to = Point3::new(4.0, -2.0, 8.0);
+    let up = Vec3::new(1.0, 1.0, 0.0);
+    let t = view_transform(from, to, up);
+    let expected = Matrix4::from_vector(&[
+      -0.50709, 0.50709, 0.67612, -2.36643, 0.76772, 0.60609, 0.12122, -2.82843, -0.35857, 0.59761,
+      -0.71714, 0.00000, 0.00000, 0.00000, 0.00000, 1.00000,
+    ]);
+    assert_eq!(expected, t);
+  }
+  #[test]
+  fn a_transform_caches_its_inverse_and_inverse_transpose() {
+    let m = scaling(1.0, 0.5, 1.0) * rotation_z(std::f64::consts::PI / 5.0);
+    let t = Transform::new(m);
+    assert_eq!(m, t);
+    assert_eq!(&m.try_inverse().unwrap(), t.inverse());
+    assert_eq!(&m.try_inverse().unwrap().transpose(), t.inverse_transpose());
+    let replaced: Transform = translation(1.0, 2.0, 3.0).into();
+    assert_eq!(&translation(-1.0, -2.0, -3.0), replaced.inverse());
+  }
+  #[test]
+  fn a_singular_transform_has_no_inverse() {
+    let t = Transform::new(scaling(0.0, 1.0, 1.0));
+    assert!(t.is_singular());
+    assert_eq!(Err(SingularMatrix), t.try_inverse());
+  }
 }
@@ -0,0 +1,291 @@
+use crate::intersections::{Intersection, EPSILON};
+use crate::materials::Material;
+use crate::matrix::Matrix4;
+use crate::rays::Ray;
+use crate::shapes::Shape;
+use crate::transformations::Transform;
+use crate::tuple::{Point3, Vec3};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Triangle {
+    pub p1: Point3,
+    pub p2: Point3,
+    pub p3: Point3,
+    pub e1: Vec3,
+    pub e2: Vec3,
+    pub normal: Vec3,
+    pub transform: Transform,
+    pub material: Material,
+    parent_transform: Transform,
+}
+
+impl Triangle {
+    pub fn new(p1: Point3, p2: Point3, p3: Point3) -> Triangle {
+        let e1 = p2 - p1;
+        let e2 = p3 - p1;
+        Triangle {
+            p1,
+            p2,
+            p3,
+            e1,
+            e2,
+            normal: e2.cross(e1).normalize(),
+            transform: Transform::identity(),
+            material: Material::default(),
+            parent_transform: Transform::identity(),
+        }
+    }
+}
+
+// A triangle with a normal for each vertex, the normal at a hit
+// is interpolated from them using the barycentric u and v of the hit
+#[derive(Debug, PartialEq, Clone)]
+pub struct SmoothTriangle {
+    pub p1: Point3,
+    pub p2: Point3,
+    pub p3: Point3,
+    pub n1: Vec3,
+    pub n2: Vec3,
+    pub n3: Vec3,
+    pub e1: Vec3,
+    pub e2: Vec3,
+    pub transform: Transform,
+    pub material: Material,
+    parent_transform: Transform,
+}
+
+impl SmoothTriangle {
+    pub fn new(p1: Point3, p2: Point3, p3: Point3, n1: Vec3, n2: Vec3, n3: Vec3) -> SmoothTriangle {
+        SmoothTriangle {
+            p1,
+            p2,
+            p3,
+            n1,
+            n2,
+            n3,
+            e1: p2 - p1,
+            e2: p3 - p1,
+            transform: Transform::identity(),
+            material: Material::default(),
+            parent_transform: Transform::identity(),
+        }
+    }
+}
+
+// Möller-Trumbore - gives t and the barycentric u and v of the hit
+fn intersect_triangle(p1: Point3, e1: Vec3, e2: Vec3, ray: &Ray) -> Option<(f64, f64, f64)> {
+    let dir_cross_e2 = ray.direction.cross(e2);
+    let det = e1.dot(dir_cross_e2);
+    // The ray is parallel to the triangle
+    if det.abs() < EPSILON {
+        return None;
+    }
+    let f = 1.0 / det;
+    let p1_to_origin = ray.origin - p1;
+    let u = f * p1_to_origin.dot(dir_cross_e2);
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let origin_cross_e1 = p1_to_origin.cross(e1);
+    let v = f * ray.direction.dot(origin_cross_e1);
+    if v < 0.0 || (u + v) > 1.0 {
+        return None;
+    }
+    let t = f * e2.dot(origin_cross_e1);
+    Some((t, u, v))
+}
+
+impl Shape for Triangle {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn set_transform(&mut self, t: Matrix4) {
+        self.transform = Transform::new(t);
+    }
+
+    fn parent_transform(&self) -> &Transform {
+        &self.parent_transform
+    }
+
+    fn set_parent_transform(&mut self, t: Matrix4) {
+        self.parent_transform = Transform::new(t);
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_material(&mut self, m: Material) {
+        self.material = m;
+    }
+
+    fn local_intersect(&self, ray: Ray) -> Vec<Intersection<'_>> {
+        match intersect_triangle(self.p1, self.e1, self.e2, &ray) {
+            Some((t, u, v)) => vec![Intersection::with_uv(t, self, u, v)],
+            None => Vec::new(),
+        }
+    }
+
+    fn local_normal_at(&self, _object_point: Point3) -> Vec3 {
+        self.normal
+    }
+}
+
+impl Shape for SmoothTriangle {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn set_transform(&mut self, t: Matrix4) {
+        self.transform = Transform::new(t);
+    }
+
+    fn parent_transform(&self) -> &Transform {
+        &self.parent_transform
+    }
+
+    fn set_parent_transform(&mut self, t: Matrix4) {
+        self.parent_transform = Transform::new(t);
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_material(&mut self, m: Material) {
+        self.material = m;
+    }
+
+    fn local_intersect(&self, ray: Ray) -> Vec<Intersection<'_>> {
+        match intersect_triangle(self.p1, self.e1, self.e2, &ray) {
+            Some((t, u, v)) => vec![Intersection::with_uv(t, self, u, v)],
+            None => Vec::new(),
+        }
+    }
+
+    // Without a hit there is nothing to interpolate, so use the flat face normal
+    fn local_normal_at(&self, _object_point: Point3) -> Vec3 {
+        self.e2.cross(self.e1).normalize()
+    }
+
+    fn local_normal_at_hit(&self, _object_point: Point3, hit: &Intersection) -> Vec3 {
+        self.n2 * hit.u + self.n3 * hit.v + self.n1 * (1.0 - hit.u - hit.v)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+
+mod tests {
+    use super::*;
+    use crate::intersections::{intersections, prepare_computations};
+    use crate::shapes::same_shape;
+
+    fn default_triangle() -> Triangle {
+        Triangle::new(
+            Point3::new(0.0, 1.0, 0.0),
+            Point3::new(-1.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+        )
+    }
+
+    fn default_smooth_triangle() -> SmoothTriangle {
+        SmoothTriangle::new(
+            Point3::new(0.0, 1.0, 0.0),
+            Point3::new(-1.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(-1.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+        )
+    }
+
+    #[test]
+    fn constructing_a_triangle() {
+        let t = default_triangle();
+        assert_eq!(Point3::new(0.0, 1.0, 0.0), t.p1);
+        assert_eq!(Point3::new(-1.0, 0.0, 0.0), t.p2);
+        assert_eq!(Point3::new(1.0, 0.0, 0.0), t.p3);
+        assert_eq!(Vec3::new(-1.0, -1.0, 0.0), t.e1);
+        assert_eq!(Vec3::new(1.0, -1.0, 0.0), t.e2);
+        assert_eq!(Vec3::new(0.0, 0.0, -1.0), t.normal);
+    }
+    #[test]
+    fn finding_the_normal_on_a_triangle() {
+        let t = default_triangle();
+        let n1 = t.local_normal_at(Point3::new(0.0, 0.5, 0.0));
+        let n2 = t.local_normal_at(Point3::new(-0.5, 0.75, 0.0));
+        let n3 = t.local_normal_at(Point3::new(0.5, 0.25, 0.0));
+        assert_eq!(t.normal, n1);
+        assert_eq!(t.normal, n2);
+        assert_eq!(t.normal, n3);
+    }
+    #[test]
+    fn intersecting_a_ray_parallel_to_the_triangle() {
+        let t = default_triangle();
+        let r = Ray::new(Point3::new(0.0, -1.0, -2.0), Vec3::new(0.0, 1.0, 0.0));
+        assert_eq!(0, t.local_intersect(r).len());
+    }
+    #[test]
+    fn a_ray_misses_the_p1_p3_edge() {
+        let t = default_triangle();
+        let r = Ray::new(Point3::new(1.0, 1.0, -2.0), Vec3::new(0.0, 0.0, 1.0));
+        assert_eq!(0, t.local_intersect(r).len());
+    }
+    #[test]
+    fn a_ray_misses_the_p1_p2_edge() {
+        let t = default_triangle();
+        let r = Ray::new(Point3::new(-1.0, 1.0, -2.0), Vec3::new(0.0, 0.0, 1.0));
+        assert_eq!(0, t.local_intersect(r).len());
+    }
+    #[test]
+    fn a_ray_misses_the_p2_p3_edge() {
+        let t = default_triangle();
+        let r = Ray::new(Point3::new(0.0, -1.0, -2.0), Vec3::new(0.0, 0.0, 1.0));
+        assert_eq!(0, t.local_intersect(r).len());
+    }
+    #[test]
+    fn a_ray_strikes_a_triangle() {
+        let t = default_triangle();
+        let r = Ray::new(Point3::new(0.0, 0.5, -2.0), Vec3::new(0.0, 0.0, 1.0));
+        let xs = t.local_intersect(r);
+        assert_eq!(1, xs.len());
+        assert_eq!(2.0, xs[0].t);
+        assert!(same_shape(&t, xs[0].object));
+    }
+    #[test]
+    fn constructing_a_smooth_triangle() {
+        let tri = default_smooth_triangle();
+        assert_eq!(Point3::new(0.0, 1.0, 0.0), tri.p1);
+        assert_eq!(Point3::new(-1.0, 0.0, 0.0), tri.p2);
+        assert_eq!(Point3::new(1.0, 0.0, 0.0), tri.p3);
+        assert_eq!(Vec3::new(0.0, 1.0, 0.0), tri.n1);
+        assert_eq!(Vec3::new(-1.0, 0.0, 0.0), tri.n2);
+        assert_eq!(Vec3::new(1.0, 0.0, 0.0), tri.n3);
+    }
+    #[test]
+    fn an_intersection_with_a_smooth_triangle_stores_u_and_v() {
+        let tri = default_smooth_triangle();
+        let r = Ray::new(Point3::new(-0.2, 0.3, -2.0), Vec3::new(0.0, 0.0, 1.0));
+        let xs = tri.local_intersect(r);
+        assert!((0.45 - xs[0].u).abs() < EPSILON);
+        assert!((0.25 - xs[0].v).abs() < EPSILON);
+    }
+    #[test]
+    fn a_smooth_triangle_uses_u_and_v_to_interpolate_the_normal() {
+        let tri = default_smooth_triangle();
+        let i = Intersection::with_uv(1.0, &tri, 0.45, 0.25);
+        let n = tri.normal_at_hit(Point3::new(0.0, 0.0, 0.0), &i);
+        assert_eq!(Vec3::new(-0.5547, 0.83205, 0.0), n);
+    }
+    #[test]
+    fn preparing_the_normal_on_a_smooth_triangle() {
+        let tri = default_smooth_triangle();
+        let i = Intersection::with_uv(1.0, &tri, 0.45, 0.25);
+        let r = Ray::new(Point3::new(-0.2, 0.3, -2.0), Vec3::new(0.0, 0.0, 1.0));
+        let xs = intersections(vec![i]);
+        let comps = prepare_computations(&xs[0], &r, &xs);
+        assert_eq!(Vec3::new(-0.5547, 0.83205, 0.0), comps.normalv);
+    }
+}
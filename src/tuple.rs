@@ -1,64 +1,42 @@
+use crate::simd;
+use std::convert::TryFrom;
+use std::fmt;
 use std::ops::{Add, Div, Mul, Neg, Sub};
 
+// A homogeneous 4-tuple, the raw form points and vectors take in matrix math.
+// Geometry uses Point3 and Vec3, which only allow the meaningful operations.
 #[derive(Debug, Copy, Clone)]
 pub struct Tuple(pub f64, pub f64, pub f64, pub f64);
 
 impl Tuple {
-    pub fn point(x: f64, y: f64, z: f64) -> Tuple {
-        Tuple(x, y, z, 1.0)
-    }
-    pub fn vector(x: f64, y: f64, z: f64) -> Tuple {
-        Tuple(x, y, z, 0.0)
-    }
-    pub fn color(r: f64, g: f64, b: f64) -> Tuple {
-        Tuple(r, g, b, 0.0)
-    }
     pub fn magnitude(&self) -> f64 {
-        (self.0 * self.0 + self.1 * self.1 + self.2 * self.2 + self.3 * self.3).sqrt()
+        self.dot(*self).sqrt()
     }
     pub fn normalize(&self) -> Tuple {
-        let m = self.magnitude();
-        Tuple(self.0 / m, self.1 / m, self.2 / m, self.3 / m)
+        Tuple::from_lanes(simd::normalize(self.lanes()))
     }
     pub fn dot(&self, other: Tuple) -> f64 {
-        self.0 * other.0 + self.1 * other.1 + self.2 * other.2 + self.3 * other.3
-    }
-    pub fn cross(&self, other: Tuple) -> Tuple {
-        Tuple::vector(
-            self.1 * other.2 - self.2 * other.1,
-            self.2 * other.0 - self.0 * other.2,
-            self.0 * other.1 - self.1 * other.0,
-        )
+        simd::dot(self.lanes(), other.lanes())
     }
-    pub fn set_w(&mut self, new_w: f64) {
-        self.3 = new_w;
+    pub(crate) fn lanes(self) -> simd::Lanes {
+        [self.0, self.1, self.2, self.3]
     }
-    pub fn reflect(&self, normal: Tuple) -> Tuple {
-        *self - normal * 2.0 * self.dot(normal)
+    pub(crate) fn from_lanes(l: simd::Lanes) -> Tuple {
+        Tuple(l[0], l[1], l[2], l[3])
     }
 }
 
 impl Add for Tuple {
     type Output = Tuple;
     fn add(self, other: Tuple) -> Tuple {
-        Tuple(
-            self.0 + other.0,
-            self.1 + other.1,
-            self.2 + other.2,
-            self.3 + other.3,
-        )
+        Tuple::from_lanes(simd::add(self.lanes(), other.lanes()))
     }
 }
 
 impl Sub for Tuple {
     type Output = Tuple;
     fn sub(self, other: Tuple) -> Tuple {
-        Tuple(
-            self.0 - other.0,
-            self.1 - other.1,
-            self.2 - other.2,
-            self.3 - other.3,
-        )
+        Tuple::from_lanes(simd::sub(self.lanes(), other.lanes()))
     }
 }
 
@@ -82,14 +60,6 @@ impl Div<f64> for Tuple {
     }
 }
 
-// Hadamard product for colors
-impl Mul<Tuple> for Tuple {
-    type Output = Tuple;
-    fn mul(self, c2: Tuple) -> Tuple {
-        Tuple(self.0 * c2.0, self.1 * c2.1, self.2 * c2.2, self.3 * c2.3)
-    }
-}
-
 impl PartialEq for Tuple {
     fn eq(&self, other: &Tuple) -> bool {
         let eps = 1e-5;
@@ -100,49 +70,261 @@ impl PartialEq for Tuple {
     }
 }
 
+// Compares the three components with the same tolerance as Tuple
+fn approx_eq3(a: (f64, f64, f64), b: (f64, f64, f64)) -> bool {
+    let eps = 1e-5;
+    (a.0 - b.0).abs() < eps && (a.1 - b.1).abs() < eps && (a.2 - b.2).abs() < eps
+}
+
+// A position in space, w = 1 in homogeneous form
+#[derive(Debug, Copy, Clone)]
+pub struct Point3(pub f64, pub f64, pub f64);
+
+impl Point3 {
+    pub fn new(x: f64, y: f64, z: f64) -> Point3 {
+        Point3(x, y, z)
+    }
+    pub fn origin() -> Point3 {
+        Point3(0.0, 0.0, 0.0)
+    }
+    pub(crate) fn lanes(self) -> simd::Lanes {
+        [self.0, self.1, self.2, 1.0]
+    }
+}
+
+// A direction or offset, w = 0 in homogeneous form
+#[derive(Debug, Copy, Clone)]
+pub struct Vec3(pub f64, pub f64, pub f64);
+
+impl Vec3 {
+    pub fn new(x: f64, y: f64, z: f64) -> Vec3 {
+        Vec3(x, y, z)
+    }
+    pub fn magnitude(&self) -> f64 {
+        self.dot(*self).sqrt()
+    }
+    pub fn normalize(&self) -> Vec3 {
+        Vec3::from_lanes(simd::normalize(self.lanes()))
+    }
+    pub fn dot(&self, other: Vec3) -> f64 {
+        simd::dot(self.lanes(), other.lanes())
+    }
+    pub fn cross(&self, other: Vec3) -> Vec3 {
+        Vec3::from_lanes(simd::cross(self.lanes(), other.lanes()))
+    }
+    pub fn reflect(&self, normal: Vec3) -> Vec3 {
+        *self - normal * 2.0 * self.dot(normal)
+    }
+    pub(crate) fn lanes(self) -> simd::Lanes {
+        [self.0, self.1, self.2, 0.0]
+    }
+    pub(crate) fn from_lanes(l: simd::Lanes) -> Vec3 {
+        Vec3(l[0], l[1], l[2])
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Color(pub f64, pub f64, pub f64);
+
+impl Color {
+    pub fn new(r: f64, g: f64, b: f64) -> Color {
+        Color(r, g, b)
+    }
+    pub fn black() -> Color {
+        Color(0.0, 0.0, 0.0)
+    }
+}
+
+impl Sub for Point3 {
+    type Output = Vec3;
+    fn sub(self, other: Point3) -> Vec3 {
+        Vec3::from_lanes(simd::sub(self.lanes(), other.lanes()))
+    }
+}
+
+impl Add<Vec3> for Point3 {
+    type Output = Point3;
+    fn add(self, v: Vec3) -> Point3 {
+        Point3(self.0 + v.0, self.1 + v.1, self.2 + v.2)
+    }
+}
+
+impl Sub<Vec3> for Point3 {
+    type Output = Point3;
+    fn sub(self, v: Vec3) -> Point3 {
+        Point3(self.0 - v.0, self.1 - v.1, self.2 - v.2)
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Vec3;
+    fn add(self, other: Vec3) -> Vec3 {
+        Vec3::from_lanes(simd::add(self.lanes(), other.lanes()))
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Vec3;
+    fn sub(self, other: Vec3) -> Vec3 {
+        Vec3::from_lanes(simd::sub(self.lanes(), other.lanes()))
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Vec3;
+    fn neg(self) -> Vec3 {
+        Vec3(-self.0, -self.1, -self.2)
+    }
+}
+
+impl Mul<f64> for Vec3 {
+    type Output = Vec3;
+    fn mul(self, f: f64) -> Vec3 {
+        Vec3(self.0 * f, self.1 * f, self.2 * f)
+    }
+}
+
+impl Div<f64> for Vec3 {
+    type Output = Vec3;
+    fn div(self, f: f64) -> Vec3 {
+        Vec3(self.0 / f, self.1 / f, self.2 / f)
+    }
+}
+
+impl Add for Color {
+    type Output = Color;
+    fn add(self, other: Color) -> Color {
+        Color(self.0 + other.0, self.1 + other.1, self.2 + other.2)
+    }
+}
+
+impl Sub for Color {
+    type Output = Color;
+    fn sub(self, other: Color) -> Color {
+        Color(self.0 - other.0, self.1 - other.1, self.2 - other.2)
+    }
+}
+
+impl Mul<f64> for Color {
+    type Output = Color;
+    fn mul(self, f: f64) -> Color {
+        Color(self.0 * f, self.1 * f, self.2 * f)
+    }
+}
+
+impl Div<f64> for Color {
+    type Output = Color;
+    fn div(self, f: f64) -> Color {
+        Color(self.0 / f, self.1 / f, self.2 / f)
+    }
+}
+
+// Hadamard product
+impl Mul<Color> for Color {
+    type Output = Color;
+    fn mul(self, c2: Color) -> Color {
+        Color(self.0 * c2.0, self.1 * c2.1, self.2 * c2.2)
+    }
+}
+
+impl PartialEq for Point3 {
+    fn eq(&self, other: &Point3) -> bool {
+        approx_eq3((self.0, self.1, self.2), (other.0, other.1, other.2))
+    }
+}
+
+impl PartialEq for Vec3 {
+    fn eq(&self, other: &Vec3) -> bool {
+        approx_eq3((self.0, self.1, self.2), (other.0, other.1, other.2))
+    }
+}
+
+impl PartialEq for Color {
+    fn eq(&self, other: &Color) -> bool {
+        approx_eq3((self.0, self.1, self.2), (other.0, other.1, other.2))
+    }
+}
+
+impl From<Point3> for Tuple {
+    fn from(p: Point3) -> Tuple {
+        Tuple(p.0, p.1, p.2, 1.0)
+    }
+}
+
+impl From<Vec3> for Tuple {
+    fn from(v: Vec3) -> Tuple {
+        Tuple(v.0, v.1, v.2, 0.0)
+    }
+}
+
+// A tuple whose w is not the one of the type it is converted to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WrongW(pub f64);
+
+impl fmt::Display for WrongW {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Tuple has w = {}, a point needs 1 and a vector 0",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for WrongW {}
+
+impl TryFrom<Tuple> for Point3 {
+    type Error = WrongW;
+    fn try_from(t: Tuple) -> Result<Point3, WrongW> {
+        if (t.3 - 1.0).abs() < 1e-5 {
+            Ok(Point3(t.0, t.1, t.2))
+        } else {
+            Err(WrongW(t.3))
+        }
+    }
+}
+
+impl TryFrom<Tuple> for Vec3 {
+    type Error = WrongW;
+    fn try_from(t: Tuple) -> Result<Vec3, WrongW> {
+        if t.3.abs() < 1e-5 {
+            Ok(Vec3(t.0, t.1, t.2))
+        } else {
+            Err(WrongW(t.3))
+        }
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::float_cmp)]
 
 mod tests {
     use super::*;
     #[test]
-    fn tuple_w1_is_a_point() {
-        let tp = Tuple(4.3, -4.2, 3.1, 1.0);
-        let Tuple(_, _, _, w) = tp;
-        assert_eq!(1.0, w);
-    }
-    #[test]
-    fn tuple_w0_is_a_vector() {
-        let x_in = 4.3;
-        let y_in = -4.2;
-        let z_in = 3.1;
-        let tp = Tuple(x_in, y_in, z_in, 0.0);
-        let Tuple(_, _, _, w) = tp;
-        assert_eq!(0.0, w);
+    fn a_point_is_a_tuple_with_w1() {
+        let p = Point3::new(4.3, -4.2, 3.1);
+        assert_eq!(Tuple(4.3, -4.2, 3.1, 1.0), Tuple::from(p));
+        assert_eq!(Ok(p), Point3::try_from(Tuple(4.3, -4.2, 3.1, 1.0)));
     }
     #[test]
-    fn point_creates_a_tuple_with_w1() {
-        let x_in = 4.3;
-        let y_in = -4.2;
-        let z_in = 3.1;
-        let p = Tuple::point(x_in, y_in, z_in);
-        let Tuple(x, y, z, w) = p;
-        assert_eq!(x_in, x);
-        assert_eq!(y_in, y);
-        assert_eq!(z_in, z);
-        assert_eq!(1.0, w);
+    fn a_vector_is_a_tuple_with_w0() {
+        let v = Vec3::new(4.3, -4.2, 3.1);
+        assert_eq!(Tuple(4.3, -4.2, 3.1, 0.0), Tuple::from(v));
+        assert_eq!(Ok(v), Vec3::try_from(Tuple(4.3, -4.2, 3.1, 0.0)));
     }
     #[test]
-    fn vector_creates_a_tuple_with_w0() {
-        let x_in = 4.3;
-        let y_in = -4.2;
-        let z_in = 3.1;
-        let p = Tuple::vector(x_in, y_in, z_in);
-        let Tuple(x, y, z, w) = p;
-        assert_eq!(x_in, x);
-        assert_eq!(y_in, y);
-        assert_eq!(z_in, z);
-        assert_eq!(0.0, w);
+    fn a_tuple_with_the_wrong_w_is_neither_point_nor_vector() {
+        let t = Tuple(4.3, -4.2, 3.1, 2.0);
+        assert_eq!(Err(WrongW(2.0)), Point3::try_from(t));
+        assert_eq!(Err(WrongW(2.0)), Vec3::try_from(t));
+        assert_eq!(
+            Err(WrongW(0.0)),
+            Point3::try_from(Tuple::from(Vec3::new(1.0, 2.0, 3.0)))
+        );
+        assert_eq!(
+            Err(WrongW(1.0)),
+            Vec3::try_from(Tuple::from(Point3::new(1.0, 2.0, 3.0)))
+        );
     }
     #[test]
     fn add_two_tuples() {
@@ -153,34 +335,40 @@ mod tests {
         assert_eq!(expected, actual);
     }
     #[test]
+    fn adding_a_vector_to_a_point() {
+        let p = Point3::new(3.0, -2.0, 5.0);
+        let v = Vec3::new(-2.0, 3.0, 1.0);
+        assert_eq!(Point3::new(1.0, 1.0, 6.0), p + v);
+    }
+    #[test]
     fn subtract_two_points() {
-        let t1 = Tuple::point(3.0, 2.0, 1.0);
-        let t2 = Tuple::point(5.0, 6.0, 7.0);
-        let expected = Tuple::vector(-2.0, -4.0, -6.0);
+        let t1 = Point3::new(3.0, 2.0, 1.0);
+        let t2 = Point3::new(5.0, 6.0, 7.0);
+        let expected = Vec3::new(-2.0, -4.0, -6.0);
         let actual = t1 - t2;
         assert_eq!(expected, actual);
     }
     #[test]
     fn subtract_vector_from_point() {
-        let p = Tuple::point(3.0, 2.0, 1.0);
-        let v = Tuple::vector(5.0, 6.0, 7.0);
-        let expected = Tuple::point(-2.0, -4.0, -6.0);
+        let p = Point3::new(3.0, 2.0, 1.0);
+        let v = Vec3::new(5.0, 6.0, 7.0);
+        let expected = Point3::new(-2.0, -4.0, -6.0);
         let actual = p - v;
         assert_eq!(expected, actual);
     }
     #[test]
     fn subtract_two_vectors() {
-        let v1 = Tuple::vector(3.0, 2.0, 1.0);
-        let v2 = Tuple::vector(5.0, 6.0, 7.0);
-        let expected = Tuple::vector(-2.0, -4.0, -6.0);
+        let v1 = Vec3::new(3.0, 2.0, 1.0);
+        let v2 = Vec3::new(5.0, 6.0, 7.0);
+        let expected = Vec3::new(-2.0, -4.0, -6.0);
         let actual = v1 - v2;
         assert_eq!(expected, actual);
     }
     #[test]
     fn subtract_vector_from_zero_vector() {
-        let v_zero = Tuple::vector(0.0, 0.0, 0.0);
-        let v = Tuple::vector(1.0, -2.0, 3.0);
-        let expected = Tuple::vector(-1.0, 2.0, -3.0);
+        let v_zero = Vec3::new(0.0, 0.0, 0.0);
+        let v = Vec3::new(1.0, -2.0, 3.0);
+        let expected = Vec3::new(-1.0, 2.0, -3.0);
         let actual = v_zero - v;
         assert_eq!(expected, actual);
     }
@@ -189,6 +377,7 @@ mod tests {
         let a = Tuple(1.0, -2.0, 3.0, -4.0);
         let expected = Tuple(-1.0, 2.0, -3.0, 4.0);
         assert_eq!(expected, -a);
+        assert_eq!(Vec3::new(-1.0, 2.0, -3.0), -Vec3::new(1.0, -2.0, 3.0));
     }
     #[test]
     fn multiplying_by_scalar() {
@@ -196,6 +385,7 @@ mod tests {
         let expected = Tuple(3.5, -7.0, 10.5, -14.0);
         let actual = a * 3.5;
         assert_eq!(expected, actual);
+        assert_eq!(Vec3::new(3.5, -7.0, 10.5), Vec3::new(1.0, -2.0, 3.0) * 3.5);
     }
     #[test]
     fn multiplying_by_fraction() {
@@ -210,101 +400,104 @@ mod tests {
         let expected = Tuple(0.5, -1.0, 1.5, -2.0);
         let actual = a / 2.0;
         assert_eq!(expected, actual);
+        assert_eq!(Vec3::new(0.5, -1.0, 1.5), Vec3::new(1.0, -2.0, 3.0) / 2.0);
     }
     #[test]
     fn magnitude_of_vector() {
-        assert_eq!(1.0, Tuple::vector(1.0, 0.0, 0.0).magnitude());
-        assert_eq!(1.0, Tuple::vector(0.0, 1.0, 0.0).magnitude());
-        assert_eq!(1.0, Tuple::vector(0.0, 0.0, 1.0).magnitude());
-        assert_eq!(14.0_f64.sqrt(), Tuple::vector(1.0, 2.0, 3.0).magnitude());
-        assert_eq!(14.0_f64.sqrt(), Tuple::vector(-1.0, -2.0, -3.0).magnitude());
+        assert_eq!(1.0, Vec3::new(1.0, 0.0, 0.0).magnitude());
+        assert_eq!(1.0, Vec3::new(0.0, 1.0, 0.0).magnitude());
+        assert_eq!(1.0, Vec3::new(0.0, 0.0, 1.0).magnitude());
+        assert_eq!(14.0_f64.sqrt(), Vec3::new(1.0, 2.0, 3.0).magnitude());
+        assert_eq!(14.0_f64.sqrt(), Vec3::new(-1.0, -2.0, -3.0).magnitude());
     }
     #[test]
     fn normalize_vector() {
         assert_eq!(
-            Tuple::vector(1.0, 0.0, 0.0).normalize(),
-            Tuple::vector(1.0, 0.0, 0.0)
+            Vec3::new(1.0, 0.0, 0.0).normalize(),
+            Vec3::new(1.0, 0.0, 0.0)
         );
         assert_eq!(
-            Tuple::vector(0.0, 1.0, 0.0).normalize(),
-            Tuple::vector(0.0, 1.0, 0.0)
+            Vec3::new(0.0, 1.0, 0.0).normalize(),
+            Vec3::new(0.0, 1.0, 0.0)
         );
         assert_eq!(
-            Tuple::vector(0.0, 0.0, 1.0).normalize(),
-            Tuple::vector(0.0, 0.0, 1.0)
+            Vec3::new(0.0, 0.0, 1.0).normalize(),
+            Vec3::new(0.0, 0.0, 1.0)
         );
-        let v = Tuple::vector(1.0, 2.0, 3.0);
+        let v = Vec3::new(1.0, 2.0, 3.0);
         let nv = v.magnitude();
-        let v2 = Tuple::vector(1.0 / nv, 2.0 / nv, 3.0 / nv);
+        let v2 = Vec3::new(1.0 / nv, 2.0 / nv, 3.0 / nv);
         assert_eq!(v.normalize(), v2);
     }
     #[test]
     fn magnitude_of_normalized_vector() {
-        let v = Tuple::vector(1.0, 2.0, 3.0);
+        let v = Vec3::new(1.0, 2.0, 3.0);
         assert_eq!(v.normalize().magnitude(), 1.0);
     }
     #[test]
     fn dot_product_of_two_tuples() {
-        let a = Tuple::vector(1.0, 2.0, 3.0);
-        let b = Tuple::vector(2.0, 3.0, 4.0);
+        let a = Vec3::new(1.0, 2.0, 3.0);
+        let b = Vec3::new(2.0, 3.0, 4.0);
         assert_eq!(a.dot(b), 20.0);
+        assert_eq!(Tuple::from(a).dot(Tuple::from(b)), 20.0);
     }
     #[test]
     fn cross_product_of_two_tuples() {
-        let a = Tuple::vector(1.0, 2.0, 3.0);
-        let b = Tuple::vector(2.0, 3.0, 4.0);
-        assert_eq!(a.cross(b), Tuple::vector(-1.0, 2.0, -1.0));
+        let a = Vec3::new(1.0, 2.0, 3.0);
+        let b = Vec3::new(2.0, 3.0, 4.0);
+        assert_eq!(a.cross(b), Vec3::new(-1.0, 2.0, -1.0));
+        assert_eq!(b.cross(a), Vec3::new(1.0, -2.0, 1.0));
     }
 
     #[test]
     fn colors_are_red_green_blue_tuples() {
-        let c = Tuple::color(-0.5, 0.4, 1.7);
-        let Tuple(r, g, b, _) = c;
+        let c = Color::new(-0.5, 0.4, 1.7);
+        let Color(r, g, b) = c;
         assert_eq!(-0.5, r);
         assert_eq!(0.4, g);
         assert_eq!(1.7, b);
     }
     #[test]
     fn adding_colors() {
-        let c1 = Tuple::color(0.9, 0.6, 0.75);
-        let c2 = Tuple::color(0.7, 0.1, 0.25);
-        let expected = Tuple::color(1.6, 0.7, 1.0);
+        let c1 = Color::new(0.9, 0.6, 0.75);
+        let c2 = Color::new(0.7, 0.1, 0.25);
+        let expected = Color::new(1.6, 0.7, 1.0);
         assert_eq!(expected, c1 + c2)
     }
     #[test]
     fn subtracting_colors() {
-        let c1 = Tuple::color(0.9, 0.6, 0.75);
-        let c2 = Tuple::color(0.7, 0.1, 0.25);
-        let expected = Tuple::color(0.2, 0.5, 0.5);
+        let c1 = Color::new(0.9, 0.6, 0.75);
+        let c2 = Color::new(0.7, 0.1, 0.25);
+        let expected = Color::new(0.2, 0.5, 0.5);
         assert_eq!(expected, c1 - c2)
     }
     #[test]
     fn multiplying_color_by_scalar() {
-        let c1 = Tuple::color(0.2, 0.3, 0.4);
-        let expected = Tuple::color(0.4, 0.6, 0.8);
+        let c1 = Color::new(0.2, 0.3, 0.4);
+        let expected = Color::new(0.4, 0.6, 0.8);
         assert_eq!(expected, c1 * 2.0)
     }
     #[test]
     fn multiplying_colors() {
-        let c1 = Tuple::color(1.0, 0.2, 0.4);
-        let c2 = Tuple::color(0.9, 1.0, 0.1);
-        let expected = Tuple::color(0.9, 0.2, 0.04);
+        let c1 = Color::new(1.0, 0.2, 0.4);
+        let c2 = Color::new(0.9, 1.0, 0.1);
+        let expected = Color::new(0.9, 0.2, 0.04);
         assert_eq!(expected, c1 * c2);
     }
     #[test]
     fn reflecting_a_vector_approaching_at_45_degrees() {
-        let v = Tuple::vector(1.0, -1.0, 0.0);
-        let n = Tuple::vector(0.0, 1.0, 0.0);
+        let v = Vec3::new(1.0, -1.0, 0.0);
+        let n = Vec3::new(0.0, 1.0, 0.0);
         let r = v.reflect(n);
-        let expected = Tuple::vector(1.0, 1.0, 0.0);
+        let expected = Vec3::new(1.0, 1.0, 0.0);
         assert_eq!(expected, r);
     }
     #[test]
     fn reflecting_a_vector_off_a_slanted_surface() {
-        let v = Tuple::vector(0.0, -1.0, 0.0);
-        let n = Tuple::vector(2_f64.sqrt() / 2.0, 2_f64.sqrt() / 2.0, 0.0);
+        let v = Vec3::new(0.0, -1.0, 0.0);
+        let n = Vec3::new(2_f64.sqrt() / 2.0, 2_f64.sqrt() / 2.0, 0.0);
         let r = v.reflect(n);
-        let expected = Tuple::vector(1.0, 0.0, 0.0);
+        let expected = Vec3::new(1.0, 0.0, 0.0);
         assert_eq!(expected, r);
     }
 }
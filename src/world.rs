@@ -0,0 +1,563 @@
+use crate::intersections::{
+    hit, intersections, prepare_computations, schlick, Computations, Intersection,
+};
+use crate::lights::{Light, PointLight};
+use crate::materials::Material;
+use crate::rays::Ray;
+use crate::shapes::{Shape, SingularTransform};
+use crate::spheres::Sphere;
+use crate::transformations::scaling;
+use crate::tuple::{Color, Point3, Vec3};
+
+// How many times a ray may bounce between reflective surfaces
+pub const MAX_REFLECTIONS: usize = 5;
+
+#[derive(Debug)]
+pub struct World {
+    pub objects: Vec<Box<dyn Shape>>,
+    pub lights: Vec<Box<dyn Light>>,
+}
+
+impl World {
+    pub fn new() -> World {
+        World {
+            objects: Vec::new(),
+            lights: Vec::new(),
+        }
+    }
+
+    // Objects with a singular transform are never hit, this finds the first one
+    // so it can be reported before rendering
+    pub fn check_transforms(&self) -> Result<(), SingularTransform> {
+        self.objects
+            .iter()
+            .enumerate()
+            .try_for_each(|(i, object)| object.check_transform(&format!("objects[{}]", i)))
+    }
+
+    pub fn intersect_world(&self, ray: Ray) -> Vec<Intersection<'_>> {
+        let xs = self
+            .objects
+            .iter()
+            .flat_map(|object| object.intersect(ray.clone()))
+            .collect();
+        intersections(xs)
+    }
+
+    pub fn shade_hit(&self, comps: &Computations, remaining: usize) -> Color {
+        // Every light contributes to the final color
+        let material = comps.object.material();
+        let surface = self
+            .lights
+            .iter()
+            .fold(Color::new(0.0, 0.0, 0.0), |color, light| {
+                color
+                    + material.lighting(
+                        comps.object,
+                        light.as_ref(),
+                        comps.over_point,
+                        comps.eyev,
+                        comps.normalv,
+                        light.intensity_at(comps.over_point, self),
+                    )
+            });
+        let reflected = self.reflected_color(comps, remaining);
+        let refracted = self.refracted_color(comps, remaining);
+        // For surfaces that are both reflective and transparent like glass or
+        // water, the Fresnel effect decides how much of each is seen
+        if material.reflective > 0.0 && material.transparency > 0.0 {
+            let reflectance = schlick(comps);
+            surface + reflected * reflectance + refracted * (1.0 - reflectance)
+        } else {
+            surface + reflected + refracted
+        }
+    }
+
+    // The color seen in a reflective surface, remaining limits the recursion
+    // so two facing mirrors can't bounce a ray forever
+    pub fn reflected_color(&self, comps: &Computations, remaining: usize) -> Color {
+        let reflective = comps.object.material().reflective;
+        if remaining == 0 || reflective == 0.0 {
+            return Color::new(0.0, 0.0, 0.0);
+        }
+        let reflect_ray = Ray::new(comps.over_point, comps.reflectv);
+        self.color_at(reflect_ray, remaining - 1) * reflective
+    }
+
+    // The color seen through a transparent surface, bent by Snell's law
+    pub fn refracted_color(&self, comps: &Computations, remaining: usize) -> Color {
+        let transparency = comps.object.material().transparency;
+        if remaining == 0 || transparency == 0.0 {
+            return Color::new(0.0, 0.0, 0.0);
+        }
+        // Ratio of the refractive indices, and cos/sin^2 of the refracted angle
+        let n_ratio = comps.n1 / comps.n2;
+        let cos_i = comps.eyev.dot(comps.normalv);
+        let sin2_t = n_ratio.powi(2) * (1.0 - cos_i.powi(2));
+        // Total internal reflection, no light is transmitted
+        if sin2_t > 1.0 {
+            return Color::new(0.0, 0.0, 0.0);
+        }
+        let cos_t = (1.0 - sin2_t).sqrt();
+        let direction = comps.normalv * (n_ratio * cos_i - cos_t) - comps.eyev * n_ratio;
+        let refract_ray = Ray::new(comps.under_point, direction);
+        self.color_at(refract_ray, remaining - 1) * transparency
+    }
+
+    // A point is in shadow when an object lies between it and the light
+    pub fn is_shadowed(&self, light_position: Point3, point: Point3) -> bool {
+        let v = light_position - point;
+        self.is_occluded(point, v.normalize(), v.magnitude())
+    }
+
+    // True if anything is hit going from the point in the direction within the distance
+    pub fn is_occluded(&self, point: Point3, direction: Vec3, distance: f64) -> bool {
+        let r = Ray::new(point, direction);
+        match hit(self.intersect_world(r)) {
+            Some(h) => h.t < distance,
+            None => false,
+        }
+    }
+
+    pub fn color_at(&self, ray: Ray, remaining: usize) -> Color {
+        let xs = self.intersect_world(ray.clone());
+        match hit(xs.clone()) {
+            Some(hit) => {
+                let comps = prepare_computations(&hit, &ray, &xs);
+                self.shade_hit(&comps, remaining)
+            }
+            None => Color::new(0.0, 0.0, 0.0),
+        }
+    }
+}
+
+impl Default for World {
+    // The default world from the book - two concentric spheres and a single light
+    fn default() -> World {
+        let light = PointLight::new(Point3::new(-10.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0));
+        let mut s1 = Sphere::default();
+        let mut m = Material::default();
+        m.color = Color::new(0.8, 1.0, 0.6);
+        m.diffuse = 0.7;
+        m.specular = 0.2;
+        s1.set_material(m);
+        let mut s2 = Sphere::default();
+        s2.set_transform(scaling(0.5, 0.5, 0.5));
+        World {
+            objects: vec![Box::new(s1), Box::new(s2)],
+            lights: vec![Box::new(light)],
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+
+mod tests {
+    use super::*;
+    use crate::groups::Group;
+    use crate::lights::{AreaLight, Sequence};
+    use crate::patterns::{Pattern, StripePattern};
+    use crate::planes::Plane;
+    use crate::transformations::translation;
+    use std::sync::Arc;
+
+    #[test]
+    fn creating_a_world() {
+        let w = World::new();
+        assert_eq!(0, w.objects.len());
+        assert_eq!(0, w.lights.len());
+    }
+    #[test]
+    fn the_default_world() {
+        let light = PointLight::new(Point3::new(-10.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0));
+        let w = World::default();
+        assert_eq!(2, w.objects.len());
+        let origin = Point3::new(0.0, 0.0, 0.0);
+        assert_eq!(
+            light.direction_to_light(origin),
+            w.lights[0].direction_to_light(origin)
+        );
+        assert_eq!(light.intensity, w.lights[0].intensity(origin));
+        assert_eq!(Color::new(0.8, 1.0, 0.6), w.objects[0].material().color);
+        assert_eq!(&scaling(0.5, 0.5, 0.5), w.objects[1].transform());
+    }
+    #[test]
+    fn intersect_a_world_with_a_ray() {
+        let w = World::default();
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let xs = w.intersect_world(r);
+        assert_eq!(4, xs.len());
+        assert_eq!(4.0, xs[0].t);
+        assert_eq!(4.5, xs[1].t);
+        assert_eq!(5.5, xs[2].t);
+        assert_eq!(6.0, xs[3].t);
+    }
+    #[test]
+    fn shading_an_intersection() {
+        let w = World::default();
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let shape = &w.objects[0];
+        let i = Intersection::new(4.0, shape.as_ref());
+        let comps = prepare_computations(&i, &r, std::slice::from_ref(&i));
+        let c = w.shade_hit(&comps, MAX_REFLECTIONS);
+        assert_eq!(Color::new(0.38066, 0.47583, 0.2855), c);
+    }
+    #[test]
+    fn shading_an_intersection_from_the_inside() {
+        let w = World {
+            lights: vec![Box::new(PointLight::new(
+                Point3::new(0.0, 0.25, 0.0),
+                Color::new(1.0, 1.0, 1.0),
+            ))],
+            ..World::default()
+        };
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        let shape = &w.objects[1];
+        let i = Intersection::new(0.5, shape.as_ref());
+        let comps = prepare_computations(&i, &r, std::slice::from_ref(&i));
+        let c = w.shade_hit(&comps, MAX_REFLECTIONS);
+        assert_eq!(Color::new(0.90498, 0.90498, 0.90498), c);
+    }
+    #[test]
+    fn the_color_when_a_ray_misses() {
+        let w = World::default();
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 1.0, 0.0));
+        let c = w.color_at(r, MAX_REFLECTIONS);
+        assert_eq!(Color::new(0.0, 0.0, 0.0), c);
+    }
+    #[test]
+    fn the_color_when_a_ray_hits() {
+        let w = World::default();
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let c = w.color_at(r, MAX_REFLECTIONS);
+        assert_eq!(Color::new(0.38066, 0.47583, 0.2855), c);
+    }
+    #[test]
+    fn the_color_with_an_intersection_behind_the_ray() {
+        let mut w = World::default();
+        for object in w.objects.iter_mut() {
+            let mut m = object.material().clone();
+            m.ambient = 1.0;
+            object.set_material(m);
+        }
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.75), Vec3::new(0.0, 0.0, -1.0));
+        let c = w.color_at(r, MAX_REFLECTIONS);
+        assert_eq!(w.objects[1].material().color, c);
+    }
+    #[test]
+    fn every_light_contributes_to_the_color() {
+        let mut w = World::default();
+        let single = w.color_at(
+            Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0)),
+            MAX_REFLECTIONS,
+        );
+        w.lights.push(Box::new(PointLight::new(
+            Point3::new(-10.0, 10.0, -10.0),
+            Color::new(1.0, 1.0, 1.0),
+        )));
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let c = w.color_at(r, MAX_REFLECTIONS);
+        assert_eq!(single * 2.0, c);
+    }
+    #[test]
+    fn there_is_no_shadow_when_nothing_is_collinear_with_point_and_light() {
+        let w = World::default();
+        let p = Point3::new(0.0, 10.0, 0.0);
+        assert!(!w.is_shadowed(Point3::new(-10.0, 10.0, -10.0), p));
+    }
+    #[test]
+    fn the_shadow_when_an_object_is_between_the_point_and_the_light() {
+        let w = World::default();
+        let p = Point3::new(10.0, -10.0, 10.0);
+        assert!(w.is_shadowed(Point3::new(-10.0, 10.0, -10.0), p));
+    }
+    #[test]
+    fn there_is_no_shadow_when_an_object_is_behind_the_light() {
+        let w = World::default();
+        let p = Point3::new(-20.0, 20.0, -20.0);
+        assert!(!w.is_shadowed(Point3::new(-10.0, 10.0, -10.0), p));
+    }
+    #[test]
+    fn there_is_no_shadow_when_an_object_is_behind_the_point() {
+        let w = World::default();
+        let p = Point3::new(-2.0, 2.0, -2.0);
+        assert!(!w.is_shadowed(Point3::new(-10.0, 10.0, -10.0), p));
+    }
+    #[test]
+    fn shade_hit_is_given_an_intersection_in_shadow() {
+        let mut w = World::new();
+        w.lights.push(Box::new(PointLight::new(
+            Point3::new(0.0, 0.0, -10.0),
+            Color::new(1.0, 1.0, 1.0),
+        )));
+        let s1 = Sphere::default();
+        let mut s2 = Sphere::default();
+        s2.set_transform(translation(0.0, 0.0, 10.0));
+        w.objects = vec![Box::new(s1), Box::new(s2)];
+        let r = Ray::new(Point3::new(0.0, 0.0, 5.0), Vec3::new(0.0, 0.0, 1.0));
+        let i = Intersection::new(4.0, w.objects[1].as_ref());
+        let comps = prepare_computations(&i, &r, std::slice::from_ref(&i));
+        let c = w.shade_hit(&comps, MAX_REFLECTIONS);
+        assert_eq!(Color::new(0.1, 0.1, 0.1), c);
+    }
+    #[test]
+    fn the_reflected_color_for_a_nonreflective_material() {
+        let mut w = World::default();
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        let mut m = w.objects[1].material().clone();
+        m.ambient = 1.0;
+        w.objects[1].set_material(m);
+        let i = Intersection::new(1.0, w.objects[1].as_ref());
+        let comps = prepare_computations(&i, &r, std::slice::from_ref(&i));
+        let color = w.reflected_color(&comps, MAX_REFLECTIONS);
+        assert_eq!(Color::new(0.0, 0.0, 0.0), color);
+    }
+    #[test]
+    fn the_reflected_color_for_a_reflective_material() {
+        let mut w = World::default();
+        let mut shape = Plane::default();
+        shape.material.reflective = 0.5;
+        shape.set_transform(translation(0.0, -1.0, 0.0));
+        w.objects.push(Box::new(shape));
+        let r = Ray::new(
+            Point3::new(0.0, 0.0, -3.0),
+            Vec3::new(0.0, -(2_f64.sqrt()) / 2.0, 2_f64.sqrt() / 2.0),
+        );
+        let i = Intersection::new(2_f64.sqrt(), w.objects[2].as_ref());
+        let comps = prepare_computations(&i, &r, std::slice::from_ref(&i));
+        let color = w.reflected_color(&comps, MAX_REFLECTIONS);
+        // Slightly off the book's values since over_point is offset by our smaller EPSILON
+        assert_eq!(Color::new(0.19033, 0.23792, 0.14275), color);
+    }
+    #[test]
+    fn shade_hit_with_a_reflective_material() {
+        let mut w = World::default();
+        let mut shape = Plane::default();
+        shape.material.reflective = 0.5;
+        shape.set_transform(translation(0.0, -1.0, 0.0));
+        w.objects.push(Box::new(shape));
+        let r = Ray::new(
+            Point3::new(0.0, 0.0, -3.0),
+            Vec3::new(0.0, -(2_f64.sqrt()) / 2.0, 2_f64.sqrt() / 2.0),
+        );
+        let i = Intersection::new(2_f64.sqrt(), w.objects[2].as_ref());
+        let comps = prepare_computations(&i, &r, std::slice::from_ref(&i));
+        let color = w.shade_hit(&comps, MAX_REFLECTIONS);
+        assert_eq!(Color::new(0.87676, 0.92434, 0.82917), color);
+    }
+    #[test]
+    fn color_at_with_mutually_reflective_surfaces() {
+        let mut w = World::new();
+        w.lights.push(Box::new(PointLight::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Color::new(1.0, 1.0, 1.0),
+        )));
+        let mut lower = Plane::default();
+        lower.material.reflective = 1.0;
+        lower.set_transform(translation(0.0, -1.0, 0.0));
+        let mut upper = Plane::default();
+        upper.material.reflective = 1.0;
+        upper.set_transform(translation(0.0, 1.0, 0.0));
+        w.objects = vec![Box::new(lower), Box::new(upper)];
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+        // Terminates instead of overflowing the stack
+        let c = w.color_at(r, MAX_REFLECTIONS);
+        assert!(c.0 > 0.0);
+    }
+    #[test]
+    fn the_reflected_color_at_the_maximum_recursive_depth() {
+        let mut w = World::default();
+        let mut shape = Plane::default();
+        shape.material.reflective = 0.5;
+        shape.set_transform(translation(0.0, -1.0, 0.0));
+        w.objects.push(Box::new(shape));
+        let r = Ray::new(
+            Point3::new(0.0, 0.0, -3.0),
+            Vec3::new(0.0, -(2_f64.sqrt()) / 2.0, 2_f64.sqrt() / 2.0),
+        );
+        let i = Intersection::new(2_f64.sqrt(), w.objects[2].as_ref());
+        let comps = prepare_computations(&i, &r, std::slice::from_ref(&i));
+        let color = w.reflected_color(&comps, 0);
+        assert_eq!(Color::new(0.0, 0.0, 0.0), color);
+    }
+    #[test]
+    fn the_refracted_color_with_an_opaque_surface() {
+        let w = World::default();
+        let shape = w.objects[0].as_ref();
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let xs = intersections(vec![
+            Intersection::new(4.0, shape),
+            Intersection::new(6.0, shape),
+        ]);
+        let comps = prepare_computations(&xs[0], &r, &xs);
+        let c = w.refracted_color(&comps, MAX_REFLECTIONS);
+        assert_eq!(Color::new(0.0, 0.0, 0.0), c);
+    }
+    #[test]
+    fn the_refracted_color_at_the_maximum_recursive_depth() {
+        let mut w = World::default();
+        let mut m = w.objects[0].material().clone();
+        m.transparency = 1.0;
+        m.refractive_index = 1.5;
+        w.objects[0].set_material(m);
+        let shape = w.objects[0].as_ref();
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let xs = intersections(vec![
+            Intersection::new(4.0, shape),
+            Intersection::new(6.0, shape),
+        ]);
+        let comps = prepare_computations(&xs[0], &r, &xs);
+        let c = w.refracted_color(&comps, 0);
+        assert_eq!(Color::new(0.0, 0.0, 0.0), c);
+    }
+    #[test]
+    fn the_refracted_color_under_total_internal_reflection() {
+        let mut w = World::default();
+        let mut m = w.objects[0].material().clone();
+        m.transparency = 1.0;
+        m.refractive_index = 1.5;
+        w.objects[0].set_material(m);
+        let shape = w.objects[0].as_ref();
+        let r = Ray::new(
+            Point3::new(0.0, 0.0, 2_f64.sqrt() / 2.0),
+            Vec3::new(0.0, 1.0, 0.0),
+        );
+        let xs = intersections(vec![
+            Intersection::new(-(2_f64.sqrt()) / 2.0, shape),
+            Intersection::new(2_f64.sqrt() / 2.0, shape),
+        ]);
+        // Inside the sphere, so look at the second intersection
+        let comps = prepare_computations(&xs[1], &r, &xs);
+        let c = w.refracted_color(&comps, MAX_REFLECTIONS);
+        assert_eq!(Color::new(0.0, 0.0, 0.0), c);
+    }
+    #[test]
+    fn the_refracted_color_with_a_refracted_ray() {
+        // The inner sphere is opaque and fully ambient, so the refracted
+        // ray through the glass outer sphere picks up its color
+        let mut w = World::default();
+        let mut m = w.objects[0].material().clone();
+        m.transparency = 1.0;
+        m.refractive_index = 1.5;
+        w.objects[0].set_material(m);
+        let mut m = w.objects[1].material().clone();
+        m.ambient = 1.0;
+        w.objects[1].set_material(m);
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let xs = w.intersect_world(r.clone());
+        let comps = prepare_computations(&xs[0], &r, &xs);
+        let c = w.refracted_color(&comps, MAX_REFLECTIONS);
+        assert_eq!(Color::new(1.0, 1.0, 1.0), c);
+    }
+    #[test]
+    fn shade_hit_with_a_transparent_material() {
+        let mut w = World::default();
+        let mut floor = Plane::default();
+        floor.set_transform(translation(0.0, -1.0, 0.0));
+        floor.material.transparency = 0.5;
+        floor.material.refractive_index = 1.5;
+        w.objects.push(Box::new(floor));
+        let mut ball = Sphere::default();
+        ball.material.color = Color::new(1.0, 0.0, 0.0);
+        ball.material.ambient = 0.5;
+        ball.set_transform(translation(0.0, -3.5, -0.5));
+        w.objects.push(Box::new(ball));
+        let r = Ray::new(
+            Point3::new(0.0, 0.0, -3.0),
+            Vec3::new(0.0, -(2_f64.sqrt()) / 2.0, 2_f64.sqrt() / 2.0),
+        );
+        let xs = intersections(vec![Intersection::new(2_f64.sqrt(), w.objects[2].as_ref())]);
+        let comps = prepare_computations(&xs[0], &r, &xs);
+        let color = w.shade_hit(&comps, MAX_REFLECTIONS);
+        assert_eq!(Color::new(0.93642, 0.68642, 0.68642), color);
+    }
+    #[test]
+    fn shade_hit_with_a_reflective_transparent_material() {
+        let mut w = World::default();
+        let mut floor = Plane::default();
+        floor.set_transform(translation(0.0, -1.0, 0.0));
+        floor.material.reflective = 0.5;
+        floor.material.transparency = 0.5;
+        floor.material.refractive_index = 1.5;
+        w.objects.push(Box::new(floor));
+        let mut ball = Sphere::default();
+        ball.material.color = Color::new(1.0, 0.0, 0.0);
+        ball.material.ambient = 0.5;
+        ball.set_transform(translation(0.0, -3.5, -0.5));
+        w.objects.push(Box::new(ball));
+        let r = Ray::new(
+            Point3::new(0.0, 0.0, -3.0),
+            Vec3::new(0.0, -(2_f64.sqrt()) / 2.0, 2_f64.sqrt() / 2.0),
+        );
+        let xs = intersections(vec![Intersection::new(2_f64.sqrt(), w.objects[2].as_ref())]);
+        let comps = prepare_computations(&xs[0], &r, &xs);
+        let color = w.shade_hit(&comps, MAX_REFLECTIONS);
+        assert_eq!(Color::new(0.93391, 0.69643, 0.69243), color);
+    }
+    #[test]
+    fn an_area_light_casts_a_soft_shadow() {
+        // A sphere between a floor and an area light, the edge of its shadow
+        // is partly lit
+        let mut w = World::new();
+        let mut light = AreaLight::new(
+            Point3::new(-1.0, 5.0, -1.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            4,
+            Vec3::new(0.0, 0.0, 2.0),
+            4,
+            Color::new(1.0, 1.0, 1.0),
+        );
+        light.jitter_by = Sequence::new(vec![0.5]);
+        w.lights.push(Box::new(light));
+        let mut sphere = Sphere::default();
+        sphere.set_transform(translation(0.0, 2.0, 0.0));
+        w.objects.push(Box::new(sphere));
+        let light = &w.lights[0];
+        assert_eq!(1.0, light.intensity_at(Point3::new(5.0, 0.0, 0.0), &w));
+        assert_eq!(0.0, light.intensity_at(Point3::new(0.0, 0.0, 0.0), &w));
+        let penumbra = light.intensity_at(Point3::new(1.5, 0.0, 0.0), &w);
+        assert!(penumbra > 0.0 && penumbra < 1.0);
+    }
+    #[test]
+    fn a_world_reports_an_object_with_a_singular_transform() {
+        let mut w = World::default();
+        assert_eq!(Ok(()), w.check_transforms());
+        let mut flat = Sphere::default();
+        flat.set_transform(scaling(1.0, 0.0, 1.0));
+        let mut g = Group::default();
+        g.add_child(Box::new(flat));
+        w.objects.push(Box::new(g));
+        let error = w.check_transforms().unwrap_err();
+        assert_eq!("Sphere", error.shape);
+        assert_eq!("objects[2].children[0]", error.path);
+        // Rendering still works, the flat sphere is just not visible
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        assert_eq!(
+            Color::new(0.38066, 0.47583, 0.2855),
+            w.color_at(r, MAX_REFLECTIONS)
+        );
+    }
+    #[test]
+    fn a_world_reports_and_renders_a_pattern_with_a_singular_transform() {
+        let mut w = World::default();
+        let mut pattern = StripePattern::new(Color::new(1.0, 1.0, 1.0), Color::new(0.0, 0.0, 0.0));
+        pattern.set_transform(scaling(0.0, 1.0, 1.0));
+        let mut m = w.objects[0].material().clone();
+        m.pattern = Some(Arc::new(pattern));
+        w.objects[0].set_material(m);
+        let error = w.check_transforms().unwrap_err();
+        assert_eq!("StripePattern", error.shape);
+        assert_eq!("objects[0].material.pattern", error.path);
+        // The pattern shades black, the same as a black material without it
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let mut expected = World::default();
+        let mut m = expected.objects[0].material().clone();
+        m.color = Color::new(0.0, 0.0, 0.0);
+        expected.objects[0].set_material(m);
+        assert_eq!(
+            expected.color_at(r.clone(), MAX_REFLECTIONS),
+            w.color_at(r, MAX_REFLECTIONS)
+        );
+    }
+}